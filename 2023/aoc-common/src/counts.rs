@@ -0,0 +1,27 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// How many times each distinct item appears.
+pub fn counts<T: Eq + Hash, I: IntoIterator<Item = T>>(iter: I) -> HashMap<T, usize> {
+    let mut counts = HashMap::new();
+    for item in iter {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let letters = counts("mississippi".chars());
+        assert_eq!(letters.len(), 4);
+        assert_eq!(letters[&'m'], 1);
+        assert_eq!(letters[&'i'], 4);
+        assert_eq!(letters[&'s'], 4);
+        assert_eq!(letters[&'p'], 2);
+
+        assert!(counts(Vec::<u8>::new()).is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Context, Result};
+use std::{fs, path::Path};
+
+/// Reads the puzzle input cached at `path`, downloading it first if it isn't there yet.
+///
+/// Downloading needs the session cookie from a logged in browser in `AOC_SESSION`. An input never
+/// changes once it's published, so a cached file is always used as is and never fetched again.
+pub fn fetch_input(year: u32, day: u32, path: &Path) -> Result<String> {
+    if path.exists() {
+        return fs::read_to_string(path).with_context(|| format!("Reading {}", path.display()));
+    }
+
+    let session = std::env::var("AOC_SESSION")
+        .map_err(|_| anyhow!("No {} and AOC_SESSION isn't set", path.display()))?;
+    let input = reqwest::blocking::Client::new()
+        .get(format!("https://adventofcode.com/{year}/day/{day}/input"))
+        .header(reqwest::header::COOKIE, format!("session={session}"))
+        .header(
+            reqwest::header::USER_AGENT,
+            "github.com/zshift/advent_of_code",
+        )
+        .send()?
+        .error_for_status()
+        .with_context(|| format!("Downloading the input for {year} day {day}"))?
+        .text()?;
+
+    fs::write(path, &input).with_context(|| format!("Writing {}", path.display()))?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_input_is_not_refetched() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("aoc-cached-{}.txt", std::process::id()));
+        fs::write(&path, "cached")?;
+
+        // Day 0 doesn't exist, so this only passes if the cache is read without a request.
+        let input = fetch_input(2023, 0, &path);
+        fs::remove_file(&path)?;
+        assert_eq!(input?, "cached");
+        Ok(())
+    }
+}
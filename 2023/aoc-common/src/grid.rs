@@ -0,0 +1,102 @@
+/// A 2D grid addressed by `(row, col)`.
+///
+/// Rows don't need to be the same length; every lookup is bounds checked against its own row.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: Copy> Grid<T> {
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        Grid { rows }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        self.rows.get(row).map(Vec::as_slice)
+    }
+
+    pub fn in_bounds(&self, row: usize, col: usize) -> bool {
+        row < self.rows.len() && col < self.rows[row].len()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<T> {
+        self.rows.get(row)?.get(col).copied()
+    }
+
+    /// The in-bounds cells surrounding `(row, col)`, including diagonals, as `(row, col, value)`.
+    pub fn neighbors8(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+
+        OFFSETS.iter().filter_map(move |&(dr, dc)| {
+            let r = row.checked_add_signed(dr)?;
+            let c = col.checked_add_signed(dc)?;
+            self.get(r, c).map(|value| (r, c, value))
+        })
+    }
+}
+
+impl From<&str> for Grid<u8> {
+    fn from(input: &str) -> Self {
+        Grid::new(input.lines().map(|line| line.as_bytes().to_vec()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let grid = Grid::from("ab\ncde");
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 0), Some(b'a'));
+        assert_eq!(grid.get(1, 2), Some(b'e'));
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.get(2, 0), None);
+    }
+
+    #[test]
+    fn test_in_bounds() {
+        let grid = Grid::from("ab\ncde");
+        assert!(grid.in_bounds(0, 1));
+        assert!(!grid.in_bounds(0, 2));
+        assert!(grid.in_bounds(1, 2));
+        assert!(!grid.in_bounds(2, 0));
+    }
+
+    #[test]
+    fn test_neighbors8() {
+        let grid = Grid::from("abc\ndef\nghi");
+
+        let mut center = grid.neighbors8(1, 1).map(|(_, _, c)| c).collect::<Vec<_>>();
+        center.sort();
+        assert_eq!(center, b"abcdfghi".to_vec());
+
+        let corner = grid.neighbors8(0, 0).collect::<Vec<_>>();
+        assert_eq!(corner, vec![(0, 1, b'b'), (1, 0, b'd'), (1, 1, b'e')]);
+    }
+
+    #[test]
+    fn test_neighbors8_ragged() {
+        let grid = Grid::from("a\nbcd");
+        let neighbors = grid.neighbors8(1, 1).collect::<Vec<_>>();
+        assert_eq!(neighbors, vec![(0, 0, b'a'), (1, 0, b'b'), (1, 2, b'd')]);
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Reads a day's puzzle input as named by `arg`: `-` reads stdin and anything else is a path. With
+/// no `arg`, the input is `embedded` if there is one. Otherwise it's `input.txt` in `dir`, which the
+/// `fetch` feature downloads first if it's missing.
+pub fn read_input(
+    arg: Option<&str>,
+    year: u32,
+    day: u32,
+    dir: &Path,
+    embedded: Option<&str>,
+) -> Result<String> {
+    match (arg, embedded) {
+        (Some("-"), _) => Ok(std::io::read_to_string(std::io::stdin())?),
+        (Some(path), _) => std::fs::read_to_string(path).with_context(|| format!("Reading {path}")),
+        (None, Some(input)) => Ok(input.to_string()),
+        (None, None) => day_input(year, day, dir),
+    }
+}
+
+#[cfg(feature = "fetch")]
+fn day_input(year: u32, day: u32, dir: &Path) -> Result<String> {
+    if !dir.is_dir() {
+        bail!("No input for {year} day {day}");
+    }
+    crate::fetch_input(year, day, &dir.join("input.txt"))
+}
+
+#[cfg(not(feature = "fetch"))]
+fn day_input(year: u32, day: u32, _dir: &Path) -> Result<String> {
+    bail!("No input for {year} day {day}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_input_path() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("aoc-read-{}.txt", std::process::id()));
+        std::fs::write(&path, "from a file")?;
+
+        let input = read_input(path.to_str(), 2023, 1, Path::new("."), Some("embedded"));
+        std::fs::remove_file(&path)?;
+        assert_eq!(input?, "from a file");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_input_embedded() -> Result<()> {
+        let input = read_input(None, 2023, 1, Path::new("."), Some("embedded"))?;
+        assert_eq!(input, "embedded");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_input_missing() {
+        let dir = Path::new("no-such-day");
+        let err = read_input(None, 2023, 99, dir, None).unwrap_err();
+        assert_eq!(err.to_string(), "No input for 2023 day 99");
+        assert!(read_input(Some("no-such-file.txt"), 2023, 1, dir, None).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use std::time::Instant;
+
+mod counts;
+#[cfg(feature = "fetch")]
+mod fetch;
+mod grid;
+mod input;
+mod overlap;
+mod parse;
+
+pub use counts::counts;
+#[cfg(feature = "fetch")]
+pub use fetch::fetch_input;
+pub use grid::Grid;
+pub use input::read_input;
+pub use overlap::{MergeOverlap, Overlap};
+pub use parse::{normalize_lines, numbers, parse_labeled_numbers};
+
+/// The shape shared by every day: parse the input once, then solve each part from the parsed form.
+pub trait Solution {
+    type Parsed;
+
+    fn parse(input: &str) -> Result<Self::Parsed>;
+    fn part1(parsed: &Self::Parsed) -> Result<String>;
+    fn part2(parsed: &Self::Parsed) -> Result<String>;
+}
+
+/// Parses the input and prints the answers to both parts.
+pub fn run<T: Solution>(input: &str) -> Result<()> {
+    let parsed = T::parse(input)?;
+    println!("Part 1: {}", T::part1(&parsed)?);
+    println!("Part 2: {}", T::part2(&parsed)?);
+    Ok(())
+}
+
+/// Runs `f`, printing how long it took to stderr so stdout stays just the answer.
+pub fn timed<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let value = f();
+    eprintln!("{label}: {:?}", start.elapsed());
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct LineCount;
+
+    impl Solution for LineCount {
+        type Parsed = Vec<String>;
+
+        fn parse(input: &str) -> Result<Self::Parsed> {
+            Ok(input.lines().map(String::from).collect())
+        }
+
+        fn part1(lines: &Self::Parsed) -> Result<String> {
+            Ok(lines.len().to_string())
+        }
+
+        fn part2(lines: &Self::Parsed) -> Result<String> {
+            Ok(lines.iter().map(String::len).sum::<usize>().to_string())
+        }
+    }
+
+    #[test]
+    fn test_run() -> Result<()> {
+        let lines = LineCount::parse("ab\ncde")?;
+        assert_eq!(LineCount::part1(&lines)?, "2");
+        assert_eq!(LineCount::part2(&lines)?, "5");
+        run::<LineCount>("ab\ncde")
+    }
+
+    #[test]
+    fn test_timed() {
+        assert_eq!(timed("answer", || 6 * 7), 42);
+        assert_eq!(timed("text", || "abc".to_string()), "abc");
+    }
+}
@@ -0,0 +1,311 @@
+use std::ops::{Range, RangeInclusive};
+
+/// Set-like operations on ranges.
+///
+/// Two ranges overlap when they share at least one value, so empty ranges never overlap anything.
+pub trait Overlap: Sized {
+    fn overlaps(&self, other: &Self) -> bool;
+
+    /// The smallest range covering both `self` and `other`.
+    fn merge(&self, other: &Self) -> Self;
+
+    /// The values in both `self` and `other`, or `None` if they don't overlap.
+    fn intersection(&self, other: &Self) -> Option<Self>;
+
+    /// The 0, 1, or 2 parts of `self` that aren't in `other`, in ascending order.
+    fn difference(&self, other: &Self) -> Vec<Self>;
+
+    /// Whether every value in `other` is also in `self`. An empty `other` is always contained.
+    fn contains_range(&self, other: &Self) -> bool;
+}
+
+pub trait MergeOverlap {
+    type Item;
+
+    /// Merges overlapping and touching ranges into a canonical form: non-empty, sorted by start,
+    /// and with a gap between each range and the next. The input can be in any order.
+    fn merge_overlap(&self) -> Self;
+
+    /// Same as `merge_overlap`, along with how many of the input ranges went into each merged
+    /// range. Empty input ranges are dropped, so they aren't counted.
+    fn merge_overlap_counted(&self) -> Vec<(Self::Item, usize)>;
+}
+
+/// Sorts `ranges` by `start` and merges each one into the last merged range whenever `joins` says
+/// there's no gap between them.
+fn merge_sorted<R: Overlap, K: Ord>(
+    mut ranges: Vec<R>,
+    start: impl Fn(&R) -> K,
+    joins: impl Fn(&R, &R) -> bool,
+) -> Vec<(R, usize)> {
+    ranges.sort_unstable_by_key(|range| start(range));
+
+    // Once sorted, every range that could join the last merged one comes right after it, so
+    // merging against the last element is enough to merge transitively.
+    ranges.into_iter().fold(vec![], |mut acc, range| {
+        if let Some((last, count)) = acc.last_mut() {
+            if joins(last, &range) {
+                *last = last.merge(&range);
+                *count += 1;
+                return acc;
+            }
+        }
+
+        acc.push((range, 1));
+        acc
+    })
+}
+
+impl<T: Copy + Ord> MergeOverlap for Vec<Range<T>> {
+    type Item = Range<T>;
+
+    fn merge_overlap(&self) -> Self {
+        self.merge_overlap_counted()
+            .into_iter()
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    fn merge_overlap_counted(&self) -> Vec<(Range<T>, usize)> {
+        let ranges = self.iter().filter(|r| !r.is_empty()).cloned().collect();
+        // ranges that only touch are merged too
+        merge_sorted(ranges, |r| r.start, |last, r| r.start <= last.end)
+    }
+}
+
+impl<T: Copy + Ord> Overlap for Range<T> {
+    fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    fn merge(&self, other: &Self) -> Self {
+        self.start.min(other.start)..self.end.max(other.end)
+    }
+
+    fn intersection(&self, other: &Self) -> Option<Self> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+
+        if start < end {
+            Some(start..end)
+        } else {
+            None
+        }
+    }
+
+    fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return if self.is_empty() {
+                vec![]
+            } else {
+                vec![self.clone()]
+            };
+        }
+
+        let before = self.start..other.start;
+        let after = other.end..self.end;
+        [before, after]
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .collect()
+    }
+
+    fn contains_range(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+}
+
+// Inclusive ranges need to step past the bounds of `other`, so they're implemented per integer type.
+macro_rules! impl_overlap_inclusive {
+    ($($t:ty),*) => {
+        $(
+            impl Overlap for RangeInclusive<$t> {
+                fn overlaps(&self, other: &Self) -> bool {
+                    self.intersection(other).is_some()
+                }
+
+                fn merge(&self, other: &Self) -> Self {
+                    *self.start().min(other.start())..=*self.end().max(other.end())
+                }
+
+                fn intersection(&self, other: &Self) -> Option<Self> {
+                    let start = *self.start().max(other.start());
+                    let end = *self.end().min(other.end());
+
+                    if start <= end {
+                        Some(start..=end)
+                    } else {
+                        None
+                    }
+                }
+
+                fn difference(&self, other: &Self) -> Vec<Self> {
+                    if !self.overlaps(other) {
+                        return if self.is_empty() {
+                            vec![]
+                        } else {
+                            vec![self.clone()]
+                        };
+                    }
+
+                    let mut parts = vec![];
+                    if self.start() < other.start() {
+                        parts.push(*self.start()..=(*other.start() - 1));
+                    }
+                    if other.end() < self.end() {
+                        parts.push((*other.end() + 1)..=*self.end());
+                    }
+                    parts
+                }
+
+                fn contains_range(&self, other: &Self) -> bool {
+                    other.is_empty() || (self.start() <= other.start() && other.end() <= self.end())
+                }
+            }
+
+            impl MergeOverlap for Vec<RangeInclusive<$t>> {
+                type Item = RangeInclusive<$t>;
+
+                fn merge_overlap(&self) -> Self {
+                    self.merge_overlap_counted()
+                        .into_iter()
+                        .map(|(range, _)| range)
+                        .collect()
+                }
+
+                fn merge_overlap_counted(&self) -> Vec<(RangeInclusive<$t>, usize)> {
+                    let ranges = self.iter().filter(|r| !r.is_empty()).cloned().collect();
+                    // `1..=3` and `4..=5` have no value between them, so they merge too
+                    merge_sorted(
+                        ranges,
+                        |r| *r.start(),
+                        |last, r| *r.start() <= last.end().saturating_add(1),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_overlap_inclusive!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlaps() {
+        assert!((0..10).overlaps(&(5..15)));
+        assert!((0..10).overlaps(&(9..10)));
+        assert!(!(0..10).overlaps(&(10..15)));
+        assert!(!(0..10).overlaps(&(5..5)));
+
+        assert!((0..=10).overlaps(&(10..=15)));
+        assert!(!(0..=10).overlaps(&(11..=15)));
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!((0..10).merge(&(5..15)), 0..15);
+        assert_eq!((0..=3).merge(&(2..=5)), 0..=5);
+    }
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!((0..10).intersection(&(5..15)), Some(5..10));
+        assert_eq!((0..10).intersection(&(2..4)), Some(2..4));
+        assert_eq!((0..10).intersection(&(10..15)), None);
+        assert_eq!((0..10).intersection(&(20..30)), None);
+
+        assert_eq!((0..=10).intersection(&(10..=15)), Some(10..=10));
+        assert_eq!((0..=10).intersection(&(11..=15)), None);
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!((0..10).difference(&(3..7)), vec![0..3, 7..10]);
+        assert_eq!((0..10).difference(&(5..15)), vec![0..5]);
+        assert_eq!((5..15).difference(&(0..10)), vec![10..15]);
+        assert_eq!((0..10).difference(&(0..10)), vec![]);
+        assert_eq!((0..10).difference(&(20..30)), vec![0..10]);
+    }
+
+    #[test]
+    fn test_difference_boundaries() {
+        // Half-open ends only touch, so nothing is removed.
+        assert_eq!((0..10u64).difference(&(10..20)), vec![0..10]);
+        assert_eq!((10..20u64).difference(&(0..10)), vec![10..20]);
+        // Removing a single value at either end.
+        assert_eq!((0..10u64).difference(&(0..1)), vec![1..10]);
+        assert_eq!((0..10u64).difference(&(9..10)), vec![0..9]);
+        // `other` covering all of `self`, and an empty `self`.
+        assert_eq!((3..7u64).difference(&(0..10)), vec![]);
+        assert_eq!((5..5u64).difference(&(0..10)), vec![]);
+        assert_eq!((5..5u64).difference(&(20..30)), vec![]);
+        assert_eq!((0..u64::MAX).difference(&(1..u64::MAX)), vec![0..1]);
+    }
+
+    #[test]
+    fn test_contains_range() {
+        assert!((0..10).contains_range(&(0..10)));
+        assert!((0..10).contains_range(&(3..7)));
+        assert!((0..10).contains_range(&(0..1)));
+        assert!((0..10).contains_range(&(9..10)));
+        assert!(!(0..10).contains_range(&(9..11)));
+        assert!(!(0..10).contains_range(&(10..11)));
+        assert!(!(1..10).contains_range(&(0..1)));
+        assert!(!(3..7).contains_range(&(0..10)));
+        assert!((0..10).contains_range(&(20..20)));
+
+        assert!((0..=10).contains_range(&(10..=10)));
+        assert!(!(0..=10).contains_range(&(10..=11)));
+        assert!(!(1..=10).contains_range(&(0..=1)));
+    }
+
+    #[test]
+    fn test_merge_overlap() {
+        assert_eq!(vec![7..9, 2..5, 1..3].merge_overlap(), vec![1..5, 7..9]);
+        assert_eq!(vec![1..3, 3..5, 6..6].merge_overlap(), vec![1..5]);
+        assert_eq!(Vec::<Range<u64>>::new().merge_overlap(), vec![]);
+    }
+
+    #[test]
+    fn test_merge_overlap_counted() {
+        assert_eq!(
+            vec![20..25, 0..10, 5..15, 12..18, 30..30].merge_overlap_counted(),
+            vec![(0..18, 3), (20..25, 1)]
+        );
+        assert_eq!(
+            vec![1..=3, 4..=5, 7..=9].merge_overlap_counted(),
+            vec![(1..=5, 2), (7..=9, 1)]
+        );
+    }
+
+    #[test]
+    fn test_merge_overlap_inclusive() {
+        assert_eq!(
+            vec![1..=3, 2..=5, 7..=9].merge_overlap(),
+            vec![1..=5, 7..=9]
+        );
+        // Adjacent integers leave no gap, but 5 and 7 do.
+        assert_eq!(
+            vec![4..=5, 1..=3, 7..=7].merge_overlap(),
+            vec![1..=5, 7..=7]
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 3..=2usize;
+        assert_eq!(
+            vec![empty, 0..=usize::MAX].merge_overlap(),
+            vec![0..=usize::MAX]
+        );
+    }
+
+    #[test]
+    fn test_difference_inclusive() {
+        assert_eq!((0..=10).difference(&(3..=7)), vec![0..=2, 8..=10]);
+        assert_eq!((0..=10).difference(&(0..=10)), vec![]);
+        assert_eq!((0..=10).difference(&(10..=20)), vec![0..=9]);
+        assert_eq!((0..=10usize).difference(&(0..=4)), vec![5..=10]);
+    }
+}
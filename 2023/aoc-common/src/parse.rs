@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Context, Result};
+use std::{error::Error, str::FromStr};
+
+/// The lines of `input` with surrounding whitespace trimmed, so CRLF and LF inputs read the same.
+pub fn normalize_lines(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().map(str::trim)
+}
+
+/// Parses every whitespace-separated token in `line`, failing on the first one that doesn't parse.
+pub fn numbers<T: FromStr>(line: &str) -> Result<Vec<T>, T::Err> {
+    line.split_whitespace().map(str::parse).collect()
+}
+
+/// Parses the numbers after `label` at the start of `line` with `numbers`, e.g. `Time:` in
+/// `Time:      7  15   30`. Fails if the label is missing or a number doesn't parse.
+pub fn parse_labeled_numbers<T>(line: &str, label: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    let rest = line
+        .trim_start()
+        .strip_prefix(label)
+        .ok_or_else(|| anyhow!("Missing '{label}' label"))?;
+
+    numbers(rest).with_context(|| {
+        // `numbers` doesn't say which token failed, so find it again for the message.
+        let n = rest
+            .split_whitespace()
+            .find(|n| n.parse::<T>().is_err())
+            .unwrap_or_default();
+        format!("Invalid number '{n}' after '{label}'")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_lines() {
+        let lines = normalize_lines("a b\r\n  c\t\r\n\r\nd\r").collect::<Vec<_>>();
+        assert_eq!(lines, vec!["a b", "c", "", "d"]);
+        assert_eq!(
+            normalize_lines("a b\r\nc").collect::<Vec<_>>(),
+            normalize_lines("a b\nc").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(
+            numbers::<u32>("41 48 83  86 17"),
+            Ok(vec![41, 48, 83, 86, 17])
+        );
+        assert_eq!(numbers::<i64>("-3 0 7 -12"), Ok(vec![-3, 0, 7, -12]));
+    }
+
+    #[test]
+    fn test_numbers_whitespace() {
+        assert_eq!(numbers::<u32>(""), Ok(vec![]));
+        assert_eq!(numbers::<u32>("   "), Ok(vec![]));
+        assert_eq!(numbers::<u32>("  1 2\t3  \n"), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_parse_labeled_numbers() -> Result<()> {
+        assert_eq!(
+            parse_labeled_numbers::<u64>("Time:      7  15   30", "Time:")?,
+            vec![7, 15, 30]
+        );
+        assert_eq!(
+            parse_labeled_numbers::<u32>("  seeds: 79\t14  55 13 ", "seeds:")?,
+            vec![79, 14, 55, 13]
+        );
+        assert_eq!(parse_labeled_numbers::<u32>("Card 1", "Card")?, vec![1]);
+        assert_eq!(parse_labeled_numbers::<u32>("Time:", "Time:")?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_labeled_numbers_errors() {
+        let err = parse_labeled_numbers::<u32>("Distance: 9 40", "Time:").unwrap_err();
+        assert_eq!(err.to_string(), "Missing 'Time:' label");
+
+        let err = parse_labeled_numbers::<u32>("Time: 7 x5", "Time:").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid number 'x5' after 'Time:'");
+    }
+
+    #[test]
+    fn test_numbers_invalid() {
+        assert!(numbers::<u32>("1 two 3").is_err());
+        assert!(numbers::<u32>("-1").is_err());
+    }
+}
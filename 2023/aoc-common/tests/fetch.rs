@@ -0,0 +1,22 @@
+//! Downloads a real input, so it only runs with the `fetch` feature and `AOC_SESSION` set.
+#![cfg(feature = "fetch")]
+
+use aoc_common::fetch_input;
+
+#[test]
+fn test_fetch_input() -> anyhow::Result<()> {
+    if std::env::var("AOC_SESSION").is_err() {
+        eprintln!("AOC_SESSION isn't set, skipping");
+        return Ok(());
+    }
+
+    let path = std::env::temp_dir().join(format!("aoc-fetch-{}.txt", std::process::id()));
+    let fetched = fetch_input(2023, 6, &path)?;
+    assert!(fetched.starts_with("Time:"));
+
+    // The second call reads the cached file instead of downloading again.
+    let cached = fetch_input(2023, 6, &path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(cached, fetched);
+    Ok(())
+}
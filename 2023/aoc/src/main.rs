@@ -0,0 +1,174 @@
+use anyhow::{anyhow, bail, Result};
+use aoc_common::Solution;
+use serde::{Deserialize, Serialize};
+use std::{str::FromStr, time::Instant};
+
+const USAGE: &str = "Usage: aoc [--format text|json] <year> <day> <part> [input]";
+
+// The input is a path, `-` for stdin, or left out to use the day's own input.txt. With the `fetch`
+// feature, a missing input.txt is downloaded first.
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let format = match args.iter().position(|arg| arg == "--format") {
+        Some(i) => {
+            let format = args.get(i + 1).ok_or_else(|| anyhow!(USAGE))?.parse()?;
+            args.drain(i..=i + 1);
+            format
+        }
+        None => Format::Text,
+    };
+    let [year, day, part, rest @ ..] = args.as_slice() else {
+        bail!(USAGE);
+    };
+    let year = year.parse()?;
+    let day = day.parse()?;
+    let part = part.parse()?;
+
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("../day{day}"));
+    let input = aoc_common::read_input(
+        rest.first().map(String::as_str),
+        year,
+        day,
+        &dir,
+        embedded_input(year, day),
+    )?;
+
+    let report = report(year, day, part, &input)?;
+    match format {
+        Format::Text => println!("{} ({:.3}ms)", report.answer, report.millis),
+        Format::Json => println!("{}", serde_json::to_string(&report)?),
+    }
+    Ok(())
+}
+
+enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => bail!("Unknown format {s}, expected text or json"),
+        }
+    }
+}
+
+/// One solved puzzle part and how long solving it took.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Report {
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: String,
+    millis: f64,
+}
+
+fn report(year: u32, day: u32, part: u32, input: &str) -> Result<Report> {
+    let start = Instant::now();
+    let answer = solve(year, day, part, input)?;
+    let millis = start.elapsed().as_secs_f64() * 1000.0;
+
+    Ok(Report {
+        year,
+        day,
+        part,
+        answer,
+        millis,
+    })
+}
+
+// `None` with the `fetch` feature, so `read_input` reads the day's input.txt at run time instead.
+fn embedded_input(year: u32, day: u32) -> Option<&'static str> {
+    match (year, day) {
+        (2023, 1) => day1::INPUT,
+        (2023, 2) => day2::INPUT,
+        (2023, 3) => day3::INPUT,
+        (2023, 4) => day4::INPUT,
+        (2023, 5) => day5::INPUT,
+        (2023, 6) => day6::INPUT,
+        (2023, 7) => day7::INPUT,
+        _ => None,
+    }
+}
+
+fn solve(year: u32, day: u32, part: u32, input: &str) -> Result<String> {
+    let answer = match (year, day, part) {
+        (2023, 1, 1) => day1::solve(input)?.to_string(),
+        (2023, 1, 2) => day1::solve_with_words(input)?.to_string(),
+        (2023, 2, _) => solve_part::<day2::Day2>(part, input)?,
+        (2023, 3, 1) => input.parse::<day3::Engine>()?.sum_of_parts().to_string(),
+        (2023, 3, 2) => input.parse::<day3::Engine>()?.sum_of_gears().to_string(),
+        (2023, 4, 1) => input.parse::<day4::Game>()?.points().to_string(),
+        (2023, 4, 2) => input
+            .parse::<day4::Game>()?
+            .total_scratchcards()
+            .to_string(),
+        (2023, 5, _) => solve_part::<day5::Day5>(part, input)?,
+        (2023, 6, 1) => day6::ways_to_beat_records(input)?.to_string(),
+        (2023, 6, 2) => day6::parse_kerned(input)?.ways_to_beat_record().to_string(),
+        (2023, 7, 1) => input.parse::<day7::Plays>()?.total_winnings().to_string(),
+        (2023, 7, 2) => day7::Plays::parse_with_order(input, day7::CardOrder::Joker)?
+            .total_winnings()
+            .to_string(),
+        _ => bail!("No solver for {year} day {day} part {part}"),
+    };
+    Ok(answer)
+}
+
+fn solve_part<T: Solution>(part: u32, input: &str) -> Result<String> {
+    let parsed = T::parse(input)?;
+    match part {
+        1 => T::part1(&parsed),
+        2 => T::part2(&parsed),
+        _ => bail!("No part {part}, only 1 or 2"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() -> Result<()> {
+        let input = "\
+        Time:      7  15   30
+        Distance:  9  40  200";
+        assert_eq!(solve(2023, 6, 1, input)?, "288");
+        assert_eq!(solve(2023, 6, 2, input)?, "71503");
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_report() -> Result<()> {
+        let input = "\
+        Time:      7  15   30
+        Distance:  9  40  200";
+        let json = serde_json::to_string(&report(2023, 6, 2, input)?)?;
+
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        assert_eq!(value["answer"], "71503");
+        assert_eq!(value["year"], 2023);
+        assert_eq!(value["day"], 6);
+        assert_eq!(value["part"], 2);
+        assert!(value["millis"].is_number());
+
+        let parsed: Report = serde_json::from_str(&json)?;
+        assert_eq!(parsed.answer, "71503");
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_puzzle() {
+        let err = solve(2023, 25, 1, "").unwrap_err();
+        assert_eq!(err.to_string(), "No solver for 2023 day 25 part 1");
+
+        assert!(solve(2022, 1, 1, "").is_err());
+        assert!(solve(2023, 1, 3, "").is_err());
+        assert!(solve(2023, 5, 3, "").is_err());
+    }
+}
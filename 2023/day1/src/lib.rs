@@ -0,0 +1,273 @@
+use anyhow::{anyhow, Result};
+use std::{collections::HashMap, io::BufRead};
+
+/// This day's puzzle input, embedded when the binaries are built. `None` with the `fetch` feature,
+/// which reads (or downloads) `input.txt` at run time instead.
+#[cfg(not(feature = "fetch"))]
+pub const INPUT: Option<&str> = Some(include_str!("../input.txt"));
+#[cfg(feature = "fetch")]
+pub const INPUT: Option<&str> = None;
+
+// Get the first and last digit in a line to create a 2-digit number. The first and last can be the same character.
+// Sum up all the numbers.
+pub fn solve(input: &str) -> Result<u32> {
+    Ok(calibration_values(input)?.iter().sum())
+}
+
+// Each line's 2-digit number, in line order, before they're summed up by `solve`.
+pub fn calibration_values(input: &str) -> Result<Vec<u32>> {
+    values_with_word_map(input, &HashMap::new())
+}
+
+// Same as `solve`, but reads one line at a time, so the whole input never has to be in memory.
+pub fn solve_reader(reader: impl BufRead) -> Result<u32> {
+    let words = HashMap::new();
+    reader
+        .lines()
+        .enumerate()
+        .map(|(row, line)| calibration_value(row, &line?, &words))
+        .sum()
+}
+
+// Same as `solve`, but digits can also be spelled out as words, e.g. "zero" or "nine".
+pub fn solve_with_words(input: &str) -> Result<u32> {
+    solve_with_word_map(input, &english_words())
+}
+
+// Same as `solve_with_words`, but with any spelled-out digits, e.g. for a puzzle in another language.
+pub fn solve_with_word_map(input: &str, words: &HashMap<&str, u32>) -> Result<u32> {
+    Ok(values_with_word_map(input, words)?.iter().sum())
+}
+
+fn values_with_word_map(input: &str, words: &HashMap<&str, u32>) -> Result<Vec<u32>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(row, line)| calibration_value(row, line, words))
+        .collect()
+}
+
+// The English digit words, "zero" through "nine".
+pub fn english_words() -> HashMap<&'static str, u32> {
+    HashMap::from([
+        ("zero", 0),
+        ("one", 1),
+        ("two", 2),
+        ("three", 3),
+        ("four", 4),
+        ("five", 5),
+        ("six", 6),
+        ("seven", 7),
+        ("eight", 8),
+        ("nine", 9),
+    ])
+}
+
+// Scans in from each end of the line, so nothing is collected and the scan stops at the first hit.
+fn calibration_value(row: usize, line: &str, words: &HashMap<&str, u32>) -> Result<u32> {
+    let digit_at = |i: usize| line.get(i..).and_then(|rest| leading_digit(rest, words));
+    let first = (0..line.len()).find_map(digit_at);
+    let last = (0..line.len()).rev().find_map(digit_at);
+
+    match (first, last) {
+        (Some(first), Some(last)) => Ok(first * 10 + last),
+        _ => Err(anyhow!("No digits found on line {}", row + 1)),
+    }
+}
+
+// The digit `s` starts with, as a numeral or one of `words`. When several words match, the longest
+// wins, so the result doesn't depend on the map's iteration order.
+fn leading_digit(s: &str, words: &HashMap<&str, u32>) -> Option<u32> {
+    if let Some(digit) = s.chars().next()?.to_digit(10) {
+        return Some(digit);
+    }
+
+    words
+        .iter()
+        .filter(|(word, _)| s.starts_with(*word))
+        .max_by_key(|(word, _)| word.len())
+        .map(|(_, &digit)| digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The original implementations, which collect every digit on the line, kept as a reference.
+    fn collected_calibration_value(line: &str, words: bool) -> Option<u32> {
+        let zero = u32::from('0');
+        let mut digits: Vec<(usize, u32)> = line
+            .chars()
+            .enumerate()
+            .filter(|(_, x)| x.is_ascii_digit())
+            .map(|(i, x)| (i, u32::from(x) - zero))
+            .collect();
+        if words {
+            digits.extend(parse_number_as_word(line));
+        }
+        digits.sort_by_key(|(i, _)| *i);
+
+        match (digits.first(), digits.last()) {
+            (Some((_, first)), Some((_, last))) => Some(first * 10 + last),
+            _ => None,
+        }
+    }
+
+    fn parse_number_as_word(input: &str) -> Vec<(usize, u32)> {
+        let mut words: HashMap<&str, u32> = HashMap::new();
+        words.insert("zero", 0);
+        words.insert("one", 1);
+        words.insert("two", 2);
+        words.insert("three", 3);
+        words.insert("four", 4);
+        words.insert("five", 5);
+        words.insert("six", 6);
+        words.insert("seven", 7);
+        words.insert("eight", 8);
+        words.insert("nine", 9);
+
+        words
+            .iter()
+            .flat_map(|(&k, &v)| {
+                if input.contains(k) {
+                    input
+                        .match_indices(k)
+                        .map(|(i, _)| (i, v))
+                        .collect::<Vec<(usize, u32)>>()
+                } else {
+                    Vec::new()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_collected_digits() {
+        let lines = [
+            "1abc2",
+            "pqr3stu8vwx",
+            "a1b2c3d4e5f",
+            "treb7uchet",
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "4nineeightseven2",
+            "zoneight234",
+            "7pqrstsixteen",
+            "zero1",
+            "oneight",
+            "abc",
+        ];
+
+        for line in lines {
+            for words in [false, true] {
+                let map = if words {
+                    english_words()
+                } else {
+                    HashMap::new()
+                };
+                assert_eq!(
+                    calibration_value(0, line, &map).ok(),
+                    collected_calibration_value(line, words),
+                    "{line} {words}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test() {
+        let input = "asd1asdf23asdf4\n5asdfasdf678asdfasdf\nasdfasdfasdfasdf8asdfasdfasdfasdf";
+        assert_eq!(solve(input).unwrap(), 14 + 58 + 88);
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        let input = "asd1asdf23asdf4\r\n5asdfasdf678asdfasdf\nasdfasdfasdfasdf8asdfasdfasdfasdf\n";
+        let sum = solve_reader(std::io::Cursor::new(input)).unwrap();
+        assert_eq!(sum, 14 + 58 + 88);
+        assert_eq!(sum, solve(input).unwrap());
+
+        let err = solve_reader("1abc2\nabc".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "No digits found on line 2");
+    }
+
+    #[test]
+    fn test_calibration_values() {
+        let input = "asd1asdf23asdf4\n5asdfasdf678asdfasdf\nasdfasdfasdfasdf8asdfasdfasdfasdf";
+        assert_eq!(calibration_values(input).unwrap(), vec![14, 58, 88]);
+        assert_eq!(calibration_values("").unwrap(), vec![]);
+
+        let err = calibration_values("1abc2\nabc").unwrap_err();
+        assert_eq!(err.to_string(), "No digits found on line 2");
+    }
+
+    #[test]
+    fn no_digits() {
+        let input = "1abc2\nabc\n3abc4";
+        let err = solve(input).unwrap_err();
+        assert_eq!(err.to_string(), "No digits found on line 2");
+
+        let err = solve_with_words(input).unwrap_err();
+        assert_eq!(err.to_string(), "No digits found on line 2");
+    }
+
+    #[test]
+    fn parse_words() {
+        let input = "one2three4five";
+        let mut output = parse_number_as_word(input);
+        output.sort_by_key(|(i, _)| *i);
+        assert_eq!(output, vec![(0, 1), (4, 3), (10, 5)]);
+    }
+
+    #[test]
+    fn zero_word() {
+        assert_eq!(solve_with_words("zero1").unwrap(), 1);
+        assert_eq!(solve_with_words("abzerocd").unwrap(), 0);
+        assert_eq!(solve_with_words("3zero").unwrap(), 30);
+        assert_eq!(solve_with_words("zero7twozero").unwrap(), 0);
+        // Part 1 still only sees numerals.
+        assert!(solve("zero").is_err());
+    }
+
+    #[test]
+    fn spanish_words() {
+        let words = HashMap::from([("uno", 1), ("dos", 2), ("tres", 3)]);
+        assert_eq!(solve_with_word_map("xdosy7tresz", &words).unwrap(), 23);
+        assert_eq!(solve_with_word_map("unotwo", &words).unwrap(), 11);
+        assert!(solve_with_word_map("one", &words).is_err());
+    }
+
+    #[test]
+    fn overlapping_words() {
+        // Each map gets its own hash seed, so repeat to cover different iteration orders.
+        for _ in 0..20 {
+            let words = HashMap::from([("seis", 6), ("seiscientos", 9)]);
+            assert_eq!(solve_with_word_map("seiscientos", &words).unwrap(), 99);
+            assert_eq!(solve_with_word_map("1seis", &words).unwrap(), 16);
+        }
+    }
+
+    #[test]
+    fn solution_with_words() {
+        let inputs = [
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "4nineeightseven2",
+            "zoneight234",
+            "7pqrstsixteen",
+        ];
+        let expected_outputs = [29, 83, 13, 24, 42, 14, 76];
+
+        inputs
+            .iter()
+            .zip(expected_outputs.iter())
+            .for_each(|(input, expected_output)| {
+                let output = solve_with_words(input).unwrap();
+                assert_eq!(output, *expected_output);
+            });
+    }
+}
@@ -1,18 +1,21 @@
-use day2::*;
+use aoc_common::timed;
+use day2::Games;
+use std::error::Error;
 
-fn main() {
-    println!("{}", solve(include_str!("../../input.txt")));
+fn main() -> Result<(), Box<dyn Error>> {
+    let input = aoc_common::read_input(
+        std::env::args().nth(1).as_deref(),
+        2023,
+        2,
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        day2::INPUT,
+    )?;
+    println!("{}", timed("day2 part 2", || solve(&input))?);
+    Ok(())
 }
 
-fn solve(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let game: Game = line.parse().unwrap();
-            let (red, green, blue) = game.min_each_color();
-            red * green * blue
-        })
-        .sum()
+fn solve(input: &str) -> Result<u32, Box<dyn Error>> {
+    Ok(input.parse::<Games>()?.total_power())
 }
 
 #[cfg(test)]
@@ -27,6 +30,6 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
-        assert_eq!(solve(input), 2286);
+        assert_eq!(solve(input).unwrap(), 2286);
     }
 }
@@ -1,21 +1,78 @@
 use std::str::FromStr;
 
-use regex::Regex;
+use anyhow::anyhow;
+use aoc_common::Solution;
+
+/// This day's puzzle input, embedded when the binaries are built. `None` with the `fetch` feature,
+/// which reads (or downloads) `input.txt` at run time instead.
+#[cfg(not(feature = "fetch"))]
+pub const INPUT: Option<&str> = Some(include_str!("../input.txt"));
+#[cfg(feature = "fetch")]
+pub const INPUT: Option<&str> = None;
 
 type Error = Box<dyn std::error::Error>;
 
-#[derive(Debug)]
+/// How many cubes of each color are in the bag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bag {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+impl Bag {
+    /// The bag from the puzzle: 12 red, 13 green, and 14 blue cubes.
+    pub const STANDARD: Bag = Bag {
+        red: 12,
+        green: 13,
+        blue: 14,
+    };
+}
+
+/// A number of cubes for each color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CubeCounts {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+impl CubeCounts {
+    pub fn power(&self) -> u32 {
+        self.red * self.green * self.blue
+    }
+
+    /// The larger count of each color.
+    pub fn max_each(self, other: CubeCounts) -> CubeCounts {
+        CubeCounts {
+            red: self.red.max(other.red),
+            green: self.green.max(other.green),
+            blue: self.blue.max(other.blue),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pull {
     pub number: u32,
     pub color: Color,
 }
 
 impl Pull {
+    pub fn new(number: u32, color: Color) -> Self {
+        Pull { number, color }
+    }
+
     pub fn is_valid(&self) -> bool {
+        self.is_possible_with(&Bag::STANDARD)
+    }
+
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
         match self.color {
-            Color::Red => self.number <= 12,
-            Color::Green => self.number <= 13,
-            Color::Blue => self.number <= 14,
+            Color::Red => self.number <= bag.red,
+            Color::Green => self.number <= bag.green,
+            Color::Blue => self.number <= bag.blue,
         }
     }
 }
@@ -24,38 +81,50 @@ impl FromStr for Pull {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re = Regex::new(r"(\d+) (red|green|blue)")?;
-        let captures = re.captures(s.trim()).unwrap();
-        let number = captures.get(1).unwrap().as_str().parse::<u32>()?;
-        let color = captures.get(2).unwrap().as_str().parse::<Color>()?;
+        // "3 blue"
+        let mut split = s.split_whitespace();
+        let number = split.next().ok_or("Missing number")?.parse::<u32>()?;
+        let color = split.next().ok_or("Missing color")?.parse::<Color>()?;
         Ok(Pull { number, color })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Set {
     pub pulls: Vec<Pull>,
 }
 
 impl Set {
+    pub fn new(pulls: Vec<Pull>) -> Self {
+        Set { pulls }
+    }
+
     pub fn is_valid(&self) -> bool {
-        self.pulls.iter().all(Pull::is_valid)
+        self.is_possible_with(&Bag::STANDARD)
     }
 
-    pub fn min_each_color(&self) -> (u32, u32, u32) {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.pulls.iter().all(|pull| pull.is_possible_with(bag))
+    }
 
+    pub fn min_each_color(&self) -> CubeCounts {
+        let mut counts = [0; Color::COUNT];
         for pull in &self.pulls {
-            match pull.color {
-                Color::Red => red = red.max(pull.number),
-                Color::Green => green = green.max(pull.number),
-                Color::Blue => blue = blue.max(pull.number),
-            }
+            let count = &mut counts[pull.color.index()];
+            *count = (*count).max(pull.number);
+        }
+
+        CubeCounts {
+            red: counts[Color::Red.index()],
+            green: counts[Color::Green.index()],
+            blue: counts[Color::Blue.index()],
         }
+    }
 
-        (red, green, blue)
+    /// The power of the smallest bag that could have produced this set.
+    pub fn power(&self) -> u32 {
+        self.min_each_color().power()
     }
 }
 
@@ -66,30 +135,41 @@ impl FromStr for Set {
         let pulls = s
             .split(", ")
             .map(Pull::from_str)
-            .map(Result::unwrap)
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Set { pulls })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     pub number: u32,
     pub sets: Vec<Set>,
 }
 
 impl Game {
+    pub fn new(number: u32, sets: Vec<Set>) -> Self {
+        Game { number, sets }
+    }
+
     pub fn is_valid(&self) -> bool {
-        self.sets.iter().all(Set::is_valid)
+        self.is_possible_with(&Bag::STANDARD)
+    }
+
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.sets.iter().all(|set| set.is_possible_with(bag))
     }
 
-    pub fn min_each_color(&self) -> (u32, u32, u32) {
-        self.sets.iter().map(|set| set.min_each_color()).fold(
-            (0, 0, 0),
-            |(red, green, blue), (red2, green2, blue2)| {
-                (red.max(red2), green.max(green2), blue.max(blue2))
-            },
-        )
+    pub fn min_each_color(&self) -> CubeCounts {
+        self.sets
+            .iter()
+            .map(Set::min_each_color)
+            .fold(CubeCounts::default(), CubeCounts::max_each)
+    }
+
+    /// The power of the smallest bag that could have produced every set in this game.
+    pub fn power(&self) -> u32 {
+        self.min_each_color().power()
     }
 }
 
@@ -97,28 +177,49 @@ impl FromStr for Game {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split = s.split(": ").collect::<Vec<_>>();
-        let number = split[0].split(' ').collect::<Vec<_>>()[1]
-            .parse::<u32>()
-            .unwrap();
+        // "Game 1: 3 blue, 4 red; 1 red, 2 green"
+        let (header, sets) = s
+            .split_once(": ")
+            .ok_or("Missing ':' after the game number")?;
+        let number = header
+            .trim()
+            .strip_prefix("Game ")
+            .ok_or("Missing 'Game' label")?
+            .trim()
+            .parse::<u32>()?;
 
-        let sets = split[1]
+        let sets = sets
             .split("; ")
             .map(Set::from_str)
-            .map(Result::unwrap)
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Game { number, sets })
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Color {
     Red,
     Green,
     Blue,
 }
 
+impl Color {
+    pub const COUNT: usize = 3;
+
+    /// Every color, in the same order as `index`.
+    pub fn all() -> [Color; Color::COUNT] {
+        [Color::Red, Color::Green, Color::Blue]
+    }
+
+    /// The color's position in `all`, for keeping one value per color in an array.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
 impl FromStr for Color {
     type Err = Error;
 
@@ -131,3 +232,231 @@ impl FromStr for Color {
         }
     }
 }
+
+/// Every game in a puzzle input, one per line.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Games(pub Vec<Game>);
+
+impl Games {
+    /// The sum of the numbers of the games that are possible with the standard bag.
+    pub fn sum_of_valid_ids(&self) -> u32 {
+        self.0
+            .iter()
+            .filter(|game| game.is_valid())
+            .map(|game| game.number)
+            .sum()
+    }
+
+    pub fn total_power(&self) -> u32 {
+        self.0.iter().map(Game::power).sum()
+    }
+}
+
+impl FromStr for Games {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let games = s
+            .lines()
+            .map(Game::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Games(games))
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Games;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        input.parse().map_err(|e| anyhow!("{e}"))
+    }
+
+    fn part1(games: &Self::Parsed) -> anyhow::Result<String> {
+        Ok(games.sum_of_valid_ids().to_string())
+    }
+
+    fn part2(games: &Self::Parsed) -> anyhow::Result<String> {
+        Ok(games.total_power().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_pull_from_str() {
+        let pull: Pull = " 3 blue ".parse().unwrap();
+        assert_eq!(pull.number, 3);
+        assert!(matches!(pull.color, Color::Blue));
+
+        assert!("blue".parse::<Pull>().is_err());
+        assert!("3".parse::<Pull>().is_err());
+        assert!("3 purple".parse::<Pull>().is_err());
+    }
+
+    #[test]
+    fn test_game_from_str_errors() {
+        let err = "Game 1 3 blue, 4 red".parse::<Game>().unwrap_err();
+        assert_eq!(err.to_string(), "Missing ':' after the game number");
+
+        let err = "Game 1: 3 blue; 4 mauve".parse::<Game>().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid color");
+
+        assert!("Game 1: three blue".parse::<Game>().is_err());
+        assert!("Game one: 3 blue".parse::<Game>().is_err());
+        assert!("Round 1: 3 blue".parse::<Game>().is_err());
+        assert!(Day2::parse("Game 1: 3 blue\nGame 2 3 blue").is_err());
+    }
+
+    #[test]
+    fn test_is_possible_with() -> anyhow::Result<()> {
+        let games = Day2::parse(INPUT)?;
+        let valid = |bag: &Bag| {
+            games
+                .0
+                .iter()
+                .filter(|game| game.is_possible_with(bag))
+                .map(|game| game.number)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(valid(&Bag::STANDARD), vec![1, 2, 5]);
+
+        // game 1 needs 6 blue cubes
+        let bag = Bag {
+            blue: 5,
+            ..Bag::STANDARD
+        };
+        assert_eq!(valid(&bag), vec![2, 5]);
+
+        // game 3 needs 20 red cubes
+        let bag = Bag {
+            red: 20,
+            ..Bag::STANDARD
+        };
+        assert_eq!(valid(&bag), vec![1, 2, 3, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_each_color() -> anyhow::Result<()> {
+        let games = Day2::parse(INPUT)?;
+        assert_eq!(
+            games.0[0].min_each_color(),
+            CubeCounts {
+                red: 4,
+                green: 2,
+                blue: 6
+            }
+        );
+        assert_eq!(games.0[0].min_each_color().power(), 48);
+        Ok(())
+    }
+
+    #[test]
+    fn test_power() -> anyhow::Result<()> {
+        let powers = Day2::parse(INPUT)?
+            .0
+            .iter()
+            .map(Game::power)
+            .collect::<Vec<_>>();
+        assert_eq!(powers, vec![48, 12, 1560, 630, 36]);
+
+        let set: Set = "3 blue, 4 red".parse().map_err(|e| anyhow!("{e}"))?;
+        assert_eq!(set.power(), 0);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() -> anyhow::Result<()> {
+        let games = Day2::parse(INPUT)?;
+        let json = serde_json::to_string(&games)?;
+        assert!(json.contains(r#"{"number":3,"color":"blue"}"#));
+
+        let parsed: Vec<Game> = serde_json::from_str(&json)?;
+        assert_eq!(parsed, games.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_new() {
+        // Two pulls of the same color in one set, which the puzzle input never has.
+        let game = Game::new(
+            7,
+            vec![
+                Set::new(vec![Pull::new(3, Color::Red), Pull::new(12, Color::Red)]),
+                Set::new(vec![Pull::new(2, Color::Green), Pull::new(4, Color::Blue)]),
+            ],
+        );
+        assert!(game.is_valid());
+        assert_eq!(game.power(), 12 * 2 * 4);
+        assert_eq!(
+            game,
+            "Game 7: 3 red, 12 red; 2 green, 4 blue".parse().unwrap()
+        );
+
+        let game = Game::new(8, vec![Set::new(vec![Pull::new(13, Color::Red)])]);
+        assert!(!game.is_valid());
+        // A color that's never pulled leaves the power at 0.
+        assert_eq!(game.power(), 0);
+    }
+
+    #[test]
+    fn test_color_index() {
+        for (i, color) in Color::all().into_iter().enumerate() {
+            assert_eq!(color.index(), i);
+        }
+        assert_eq!(Color::all().len(), Color::COUNT);
+    }
+
+    #[test]
+    fn test_min_each_color_by_index() {
+        // The same minimums as matching on each color by hand.
+        let by_match = |set: &Set| {
+            let mut counts = CubeCounts::default();
+            for pull in &set.pulls {
+                match pull.color {
+                    Color::Red => counts.red = counts.red.max(pull.number),
+                    Color::Green => counts.green = counts.green.max(pull.number),
+                    Color::Blue => counts.blue = counts.blue.max(pull.number),
+                }
+            }
+            counts
+        };
+
+        let games: Games = INPUT.parse().unwrap();
+        for set in games.0.iter().flat_map(|game| &game.sets) {
+            assert_eq!(set.min_each_color(), by_match(set));
+        }
+        assert_eq!(games.total_power(), 2286);
+    }
+
+    #[test]
+    fn test_games() {
+        let games: Games = INPUT.parse().unwrap();
+        assert_eq!(games.0.len(), 5);
+        assert_eq!(games.sum_of_valid_ids(), 8);
+        assert_eq!(games.total_power(), 2286);
+
+        assert!("Game 1: 3 blue\nGame 2: 3 purple".parse::<Games>().is_err());
+    }
+
+    #[test]
+    fn test_solution() -> anyhow::Result<()> {
+        let games = Day2::parse(INPUT)?;
+        assert_eq!(Day2::part1(&games)?, "8");
+        assert_eq!(Day2::part2(&games)?, "2286");
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day3::Engine;
+
+static INPUT: &[u8] = include_bytes!("../input.txt");
+
+fn engine(c: &mut Criterion) {
+    let from_str: Engine = std::str::from_utf8(INPUT).unwrap().parse().unwrap();
+    let from_bytes = Engine::from_bytes(INPUT).unwrap();
+    assert_eq!(from_str.sum_of_parts(), from_bytes.sum_of_parts());
+    assert_eq!(from_str.sum_of_gears(), from_bytes.sum_of_gears());
+
+    let mut group = c.benchmark_group("engine");
+    group.bench_function("from_str", |b| {
+        b.iter(|| {
+            let input = std::str::from_utf8(INPUT).unwrap();
+            input.parse::<Engine>().unwrap()
+        })
+    });
+    group.bench_function("from_bytes", |b| {
+        b.iter(|| Engine::from_bytes(INPUT).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, engine);
+criterion_main!(benches);
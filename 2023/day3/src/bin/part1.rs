@@ -1,11 +1,19 @@
+use aoc_common::timed;
 use day3::Engine;
 
-fn main() {
-    println!("{}", solve(include_str!("../../input.txt")));
+fn main() -> anyhow::Result<()> {
+    let input = aoc_common::read_input(
+        std::env::args().nth(1).as_deref(),
+        2023,
+        3,
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        day3::INPUT,
+    )?;
+    println!("{}", timed("day3 part 1", || solve(&input))?);
+    Ok(())
 }
 
-fn solve(input: &str) -> u32 {
-    let engine: Engine = input.parse().unwrap();
-    engine.sum_of_parts()
+fn solve(input: &str) -> anyhow::Result<u32> {
+    let engine: Engine = input.parse()?;
+    Ok(engine.sum_of_parts())
 }
- 
\ No newline at end of file
@@ -1,10 +1,39 @@
+use aoc_common::timed;
 use day3::Engine;
 
-fn main() {
-    println!("{}", solve(include_str!("../../input.txt")));
+fn main() -> anyhow::Result<()> {
+    let input = aoc_common::read_input(
+        std::env::args().nth(1).as_deref(),
+        2023,
+        3,
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        day3::INPUT,
+    )?;
+    println!("{}", timed("day3 part 2", || solve(&input))?);
+    Ok(())
 }
 
-fn solve(input: &str) -> u32 {
-    let engine: Engine = input.parse().unwrap();
-    engine.sum_of_gears()
+fn solve(input: &str) -> anyhow::Result<u32> {
+    let engine: Engine = input.parse()?;
+    Ok(engine.sum_of_gears())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = "467..114..
+                     ...*......
+                     ..35..633.
+                     ......#...
+                     617*......
+                     .....+.58.
+                     ..592.....
+                     ......755.
+                     ...$.*....
+                     .664.598..";
+        assert_eq!(solve(input).unwrap(), 467835);
+    }
 }
@@ -1,10 +1,19 @@
+use aoc_common::timed;
 use day4::Game;
 
-fn main() {
-    println!("{}", solve(include_str!("../../input.txt")));
+fn main() -> anyhow::Result<()> {
+    let input = aoc_common::read_input(
+        std::env::args().nth(1).as_deref(),
+        2023,
+        4,
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        day4::INPUT,
+    )?;
+    println!("{}", timed("day4 part 1", || solve(&input))?);
+    Ok(())
 }
 
-fn solve(input: &str) -> u32 {
-    let game: Game = input.parse().unwrap();
-    game.points()
+fn solve(input: &str) -> anyhow::Result<u32> {
+    let game: Game = input.parse()?;
+    Ok(game.points())
 }
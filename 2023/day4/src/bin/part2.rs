@@ -1,10 +1,35 @@
+use aoc_common::timed;
 use day4::Game;
 
-fn main() {
-    println!("{}", solve(include_str!("../../input.txt")));
+fn main() -> anyhow::Result<()> {
+    let input = aoc_common::read_input(
+        std::env::args().nth(1).as_deref(),
+        2023,
+        4,
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        day4::INPUT,
+    )?;
+    println!("{}", timed("day4 part 2", || solve(&input))?);
+    Ok(())
 }
 
-fn solve(input: &str) -> u32 {
-    let game: Game = input.parse().unwrap();
-    game.total_scratchcards()
+fn solve(input: &str) -> anyhow::Result<u64> {
+    let game: Game = input.parse()?;
+    Ok(game.total_scratchcards())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+                     Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+                     Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+                     Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+                     Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+                     Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        assert_eq!(solve(input).unwrap(), 30);
+    }
 }
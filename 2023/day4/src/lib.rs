@@ -1,71 +1,125 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Context, Error, Result};
 
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+/// This day's puzzle input, embedded when the binaries are built. `None` with the `fetch` feature,
+/// which reads (or downloads) `input.txt` at run time instead.
+#[cfg(not(feature = "fetch"))]
+pub const INPUT: Option<&str> = Some(include_str!("../input.txt"));
+#[cfg(feature = "fetch")]
+pub const INPUT: Option<&str> = None;
 
 pub struct Scratchcard {
     id: u32,
     winning: Vec<u32>,
     numbers: Vec<u32>,
+    // counted on first use and reused after, since both parts need it.
+    matches: OnceLock<u32>,
 }
 
 impl FromStr for Scratchcard {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split(':');
-        let mut card_split = split
-            .next()
-            .ok_or(anyhow!("No card id found"))?
-            .split_whitespace();
+        let (header, _) = s
+            .split_once(':')
+            .ok_or(anyhow!("No winning numbers and numbers found"))?;
+        let id = match aoc_common::parse_labeled_numbers(header, "Card")?[..] {
+            [id] => id,
+            _ => return Err(anyhow!("Expected one id after 'Card'")),
+        };
 
-        let _ = card_split.next().ok_or(anyhow!("No card found"))?; // Card
-        let id: u32 = card_split.next().ok_or(anyhow!("No id found"))?.parse()?;
+        // The winning numbers are labeled by the card header, and the numbers you have by the '|'.
+        let pipe = s.find('|').ok_or(anyhow!("No numbers found"))?;
+        let (winning, numbers) = s.split_at(pipe);
 
-        let winning_numbers = split
-            .next()
-            .ok_or(anyhow!("No winning numbers and numbers found"))?;
+        let winning = number_list(
+            winning,
+            &format!("{}:", header.trim_start()),
+            "winning numbers",
+        )?;
+        let numbers = number_list(numbers, "|", "numbers you have")?;
 
-        let mut split = winning_numbers.split('|').map(str::trim);
-        let winning = split.next().ok_or(anyhow!("No winning numbers found"))?;
-        let numbers = split.next().ok_or(anyhow!("No numbers found"))?;
+        Ok(Scratchcard::new(id, winning, numbers))
+    }
+}
 
-        let winning: Vec<u32> = winning
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
+// `aoc_common::parse_labeled_numbers`, with the error also naming which list didn't parse.
+fn number_list(list: &str, label: &str, name: &str) -> Result<Vec<u32>> {
+    aoc_common::parse_labeled_numbers(list, label).with_context(|| format!("Invalid {name}"))
+}
 
-        let numbers: Vec<u32> = numbers
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
+/// One point for the first match, multiplied by `base` for each match after it.
+/// Saturates at `u32::MAX` rather than overflowing.
+fn score(matches: u32, base: u32) -> u32 {
+    match matches {
+        0 => 0,
+        matches => base.saturating_pow(matches - 1),
+    }
+}
 
-        Ok(Scratchcard {
+impl Scratchcard {
+    pub fn new(id: u32, winning: Vec<u32>, numbers: Vec<u32>) -> Self {
+        Scratchcard {
             id,
             winning,
             numbers,
-        })
+            matches: OnceLock::new(),
+        }
     }
-}
 
-impl Scratchcard {
-    pub fn matches(&self) -> u32 {
-        let mut matches: u32 = 0;
-        for number in &self.numbers {
-            if self.winning.contains(number) {
-                matches += 1;
-            }
-        }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
 
-        matches
+    pub fn winning(&self) -> &[u32] {
+        &self.winning
+    }
+
+    pub fn numbers(&self) -> &[u32] {
+        &self.numbers
+    }
+
+    /// The numbers that are also winning numbers, in the order they appear on the card.
+    pub fn matching_numbers(&self) -> Vec<u32> {
+        let winning: HashSet<&u32> = self.winning.iter().collect();
+        self.numbers
+            .iter()
+            .filter(|n| winning.contains(n))
+            .copied()
+            .collect()
+    }
+
+    pub fn matches(&self) -> u32 {
+        *self
+            .matches
+            .get_or_init(|| self.matching_numbers().len() as u32)
     }
 
     pub fn points(&self) -> u32 {
-        let matches = self.matches();
+        Self::points_for_matches(self.matches())
+    }
 
-        if matches == 0 {
-            0
+    /// Like `points`, but each match after the first multiplies the score by `base` instead of 2.
+    pub fn points_with(&self, base: u32) -> u32 {
+        score(self.matches(), base)
+    }
+
+    /// The first match is worth one point, and each match after that doubles it.
+    pub fn points_for_matches(matches: u32) -> u32 {
+        score(matches, 2)
+    }
+
+    /// The share of the card's numbers that are winning numbers, from 0.0 to 1.0.
+    pub fn win_fraction(&self) -> f64 {
+        if self.numbers.is_empty() {
+            0.0
         } else {
-            2u32.pow(matches - 1)
+            f64::from(self.matches()) / self.numbers.len() as f64
         }
     }
 }
@@ -78,22 +132,52 @@ impl FromStr for Game {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cards = s.lines().map(|line| line.trim().parse().unwrap()).collect();
+        let cards = parse_cards(s).collect::<Result<_>>()?;
 
-        Ok(Game { cards })
+        let game = Game { cards };
+        game.validate()?;
+        Ok(game)
     }
 }
 
+/// Parses one card per line as it's pulled, so callers can stop at the first bad line.
+pub fn parse_cards(input: &str) -> impl Iterator<Item = Result<Scratchcard, Error>> + '_ {
+    aoc_common::normalize_lines(input).map(str::parse)
+}
+
 impl Game {
+    /// Checks that the cards are numbered 1, 2, 3, ... in order, which the copying in
+    /// `total_scratchcards` relies on.
+    pub fn validate(&self) -> Result<()> {
+        for (expected, card) in (1..).zip(&self.cards) {
+            if card.id != expected {
+                return Err(anyhow!("Expected card {expected}, found card {}", card.id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Each card's `(id, points)`, in card order.
+    pub fn card_points(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.cards.iter().map(|card| (card.id, card.points()))
+    }
+
     pub fn points(&self) -> u32 {
-        self.cards.iter().map(Scratchcard::points).sum()
+        self.card_points().map(|(_, points)| points).sum()
+    }
+
+    /// Same as `points`, but scores the cards in parallel. Needs the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn points_par(&self) -> u32 {
+        use rayon::prelude::*;
+        self.cards.par_iter().map(Scratchcard::points).sum()
     }
 
     // For each card, find the number of matches.
     // For each x matches, the following x cards are copied.
     // Find the total number of cards.
-    pub fn total_scratchcards(&self) -> u32 {
-        let mut copies: HashMap<u32, u32> = HashMap::new();
+    pub fn total_scratchcards(&self) -> u64 {
+        let mut copies: HashMap<u32, u64> = HashMap::new();
 
         self.cards.iter().for_each(|card| {
             let id = card.id;
@@ -123,15 +207,227 @@ mod tests {
                           Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
                           Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
+    #[test]
+    fn test_cached_matches() {
+        let naive = |card: &Scratchcard| {
+            let mut matches: u32 = 0;
+            for number in &card.numbers {
+                if card.winning.contains(number) {
+                    matches += 1;
+                }
+            }
+            matches
+        };
+
+        let game: Game = INPUT.parse().unwrap();
+        for card in &game.cards {
+            assert_eq!(card.matches(), naive(card));
+        }
+        assert_eq!(
+            game.cards
+                .iter()
+                .map(Scratchcard::matches)
+                .collect::<Vec<_>>(),
+            vec![4, 2, 2, 1, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        let card = Scratchcard::new(3, vec![1, 2, 3], vec![4, 5, 6, 7]);
+        assert_eq!(card.id(), 3);
+        assert_eq!(card.winning(), &[1, 2, 3]);
+        assert_eq!(card.numbers(), &[4, 5, 6, 7]);
+        assert_eq!(card.matches(), 0);
+        assert_eq!(card.points(), 0);
+
+        // Every number wins.
+        let card = Scratchcard::new(1, vec![5, 4, 3, 2, 1], vec![1, 2, 3, 4, 5]);
+        assert_eq!(card.matches(), 5);
+        assert_eq!(card.points(), 16);
+        assert_eq!(card.win_fraction(), 1.0);
+
+        let card = Scratchcard::new(1, vec![], vec![]);
+        assert_eq!(card.matches(), 0);
+        assert_eq!(card.points(), 0);
+    }
+
+    #[test]
+    fn test_points_for_matches() {
+        assert_eq!(Scratchcard::points_for_matches(0), 0);
+        assert_eq!(Scratchcard::points_for_matches(1), 1);
+        assert_eq!(Scratchcard::points_for_matches(2), 2);
+        assert_eq!(Scratchcard::points_for_matches(5), 16);
+    }
+
+    #[test]
+    fn test_points_with() {
+        let game: Game = INPUT.parse().unwrap();
+        let card = &game.cards[0];
+        assert_eq!(card.matches(), 4);
+        assert_eq!(card.points_with(3), 27);
+        assert_eq!(card.points_with(2), card.points());
+        assert_eq!(card.points_with(1), 1);
+        assert_eq!(game.cards[4].points_with(3), 0);
+    }
+
+    #[test]
+    fn test_points_saturate() -> Result<()> {
+        let numbers = (1..=40)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let card: Scratchcard = format!("Card 1: {numbers} | {numbers}").parse()?;
+        assert_eq!(card.matches(), 40);
+        assert_eq!(card.points(), u32::MAX);
+        assert_eq!(card.points_with(10), u32::MAX);
+        Ok(())
+    }
+
+    #[test]
+    fn test_win_fraction() {
+        let game: Game = INPUT.parse().unwrap();
+        assert_eq!(game.cards[0].win_fraction(), 0.5);
+        assert_eq!(game.cards[3].win_fraction(), 0.125);
+        assert_eq!(game.cards[5].win_fraction(), 0.0);
+
+        let card: Scratchcard = "Card 1: 1 2 |".parse().unwrap();
+        assert_eq!(card.win_fraction(), 0.0);
+    }
+
+    #[test]
+    fn test_card_points() {
+        let game: Game = INPUT.parse().unwrap();
+        let points = game.card_points().collect::<Vec<_>>();
+        assert_eq!(points[..2], [(1, 8), (2, 2)]);
+        assert_eq!(points, vec![(1, 8), (2, 2), (3, 2), (4, 1), (5, 0), (6, 0)]);
+    }
+
+    #[test]
+    fn test_matching_numbers() {
+        let game: Game = INPUT.parse().unwrap();
+        let card = &game.cards[0];
+        assert_eq!(card.id(), 1);
+        assert_eq!(card.winning(), &[41, 48, 83, 86, 17]);
+        assert_eq!(card.numbers(), &[83, 86, 6, 31, 17, 9, 48, 53]);
+        assert_eq!(card.matching_numbers(), vec![83, 86, 17, 48]);
+        assert!(game.cards[4].matching_numbers().is_empty());
+    }
+
+    #[test]
+    fn test_parse_bad_number() {
+        let err = "Card 1: 41 4x 83 | 83 86"
+            .parse::<Scratchcard>()
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "Invalid winning numbers");
+        assert!(format!("{err:#}").contains("Invalid number '4x' after 'Card 1:'"));
+
+        let err = "Card 1: 41 48 83 | 83 -86"
+            .parse::<Scratchcard>()
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "Invalid numbers you have");
+        assert!(format!("{err:#}").contains("Invalid number '-86' after '|'"));
+        assert!(format!("{err:#}").contains("invalid digit"));
+    }
+
+    #[test]
+    fn test_parse_bad_header() {
+        let err = "Crd 1: 41 | 83".parse::<Scratchcard>().err().unwrap();
+        assert_eq!(err.to_string(), "Missing 'Card' label");
+
+        let err = "Card: 41 | 83".parse::<Scratchcard>().err().unwrap();
+        assert_eq!(err.to_string(), "Expected one id after 'Card'");
+
+        let err = "Card   1   2: 41 | 83"
+            .parse::<Scratchcard>()
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "Expected one id after 'Card'");
+
+        let card: Scratchcard = "Card   12: 41 | 83".parse().unwrap();
+        assert_eq!(card.id(), 12);
+    }
+
+    #[test]
+    fn test_parse_cards() -> Result<()> {
+        let ids = parse_cards(INPUT)
+            .map(|card| card.map(|card| card.id()))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(ids, vec![1, 2, 3, 4, 5, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_malformed_line() {
+        let input = "Card 1: 41 48 | 83 86
+                     Card two: 13 32 | 61 30
+                     Card 3: 1 21 | 69 82";
+
+        let mut cards = parse_cards(input);
+        assert!(cards.next().unwrap().is_ok());
+        assert!(cards.next().unwrap().is_err());
+
+        assert!(input.parse::<Game>().is_err());
+        assert!("Card 1: 41 x | 83".parse::<Game>().is_err());
+    }
+
+    #[test]
+    fn test_duplicate_id() {
+        let input = "Card 1: 41 48 | 83 86
+                     Card 2: 13 32 | 61 30
+                     Card 2: 1 21 | 69 82";
+        let err = input.parse::<Game>().err().unwrap();
+        assert_eq!(err.to_string(), "Expected card 3, found card 2");
+    }
+
+    #[test]
+    fn test_id_gap() {
+        let input = "Card 1: 41 48 | 83 86
+                     Card 3: 1 21 | 69 82";
+        let err = input.parse::<Game>().err().unwrap();
+        assert_eq!(err.to_string(), "Expected card 2, found card 3");
+
+        assert!("Card 2: 41 48 | 83 86".parse::<Game>().is_err());
+    }
+
     #[test]
     fn test_part1() {
         let scratchcard: Game = INPUT.parse().unwrap();
         assert_eq!(scratchcard.points(), 13);
     }
 
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_part1_par() {
+        let scratchcard: Game = INPUT.parse().unwrap();
+        assert_eq!(scratchcard.points_par(), scratchcard.points());
+    }
+
     #[test]
     fn test_part2() {
         let scratchcard: Game = INPUT.parse().unwrap();
         assert_eq!(scratchcard.total_scratchcards(), 30);
     }
+
+    #[test]
+    fn test_part2_beyond_u32() {
+        // Card i matches every card after it, so card i ends up with 2^(i-1) copies.
+        let n = 40;
+        let input = (1..=n)
+            .map(|id| {
+                let numbers = (1..=(n - id))
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!("Card {id}: {numbers} | {numbers}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let game: Game = input.parse().unwrap();
+        assert_eq!(game.total_scratchcards(), (1u64 << n) - 1);
+        assert!(game.total_scratchcards() > u32::MAX as u64);
+    }
 }
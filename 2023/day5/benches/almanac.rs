@@ -0,0 +1,67 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day5::{Almanac, Almanac2};
+
+static SAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+static INPUT: &str = include_str!("../input.txt");
+
+fn almanac(c: &mut Criterion) {
+    let mut group = c.benchmark_group("almanac");
+    for (name, input) in [("sample", SAMPLE), ("input", INPUT)] {
+        let almanac: Almanac = input.parse().unwrap();
+        group.throughput(Throughput::Elements(almanac.seed_count() as u64));
+        group.bench_with_input(BenchmarkId::new("seeds", name), &almanac, |b, almanac| {
+            b.iter(|| almanac.lowest_location_that_needs_a_seed())
+        });
+    }
+    group.finish();
+}
+
+fn almanac2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("almanac2");
+    for (name, input) in [("sample", SAMPLE), ("input", INPUT)] {
+        let almanac: Almanac2 = input.parse().unwrap();
+        group.throughput(Throughput::Elements(almanac.seed_ranges().len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("seed_ranges", name),
+            &almanac,
+            |b, almanac| b.iter(|| almanac.lowest_location_that_needs_a_seed()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, almanac, almanac2);
+criterion_main!(benches);
@@ -1,11 +1,19 @@
-use day5::{Almanac, Almanac2};
+use aoc_common::timed;
+use day5::Almanac2;
 
-fn main() {
-    println!("{}", solve(include_str!("../../input.txt")));
+fn main() -> anyhow::Result<()> {
+    let input = aoc_common::read_input(
+        std::env::args().nth(1).as_deref(),
+        2023,
+        5,
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+        day5::INPUT,
+    )?;
+    println!("{}", timed("day5 part 2", || solve(&input))?);
+    Ok(())
 }
 
-fn solve(input: &str) -> u64 {
-    let almanac: Almanac = input.parse().unwrap();
-    let almanac: Almanac2 = almanac.into();
-    almanac.lowest_location_that_needs_a_seed()
+fn solve(input: &str) -> anyhow::Result<u64> {
+    let almanac: Almanac2 = input.parse()?;
+    Ok(almanac.lowest_location_that_needs_a_seed())
 }
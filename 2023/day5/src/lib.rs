@@ -1,31 +1,24 @@
-use anyhow::Error;
-use std::{ops::Range, str::FromStr};
+use anyhow::{anyhow, Error};
+use aoc_common::{parse_labeled_numbers, MergeOverlap, Overlap, Solution};
+use rayon::prelude::*;
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Range,
+    str::FromStr,
+};
 
-mod utils;
+/// This day's puzzle input, embedded when the binaries are built. `None` with the `fetch` feature,
+/// which reads (or downloads) `input.txt` at run time instead.
+#[cfg(not(feature = "fetch"))]
+pub const INPUT: Option<&str> = Some(include_str!("../input.txt"));
+#[cfg(feature = "fetch")]
+pub const INPUT: Option<&str> = None;
 
-use utils::Overlap;
+/// The `(mapped, leftover)` ranges produced by [`RangeMap::apply_range`] and [`RangeMap::map_onto`].
+pub type MappedRanges = (Vec<Range<u64>>, Vec<Range<u64>>);
 
-trait MergeOverlap {
-    fn merge_overlap(&self) -> Self;
-}
-
-impl MergeOverlap for Vec<Range<u64>> {
-    fn merge_overlap(&self) -> Self {
-        self.iter().fold(vec![], |mut acc, range| {
-            if let Some(last) = acc.last_mut() {
-                if last.overlaps(range) {
-                    *last = last.merge(range);
-                    return acc;
-                }
-            }
-
-            acc.push(range.clone());
-            acc
-        })
-    }
-}
-
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RangeMap {
     pub dest: Range<u64>,
     pub src: Range<u64>,
@@ -36,9 +29,14 @@ impl FromStr for RangeMap {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split_whitespace();
-        let dest_start = split.next().unwrap().parse()?;
-        let source_start = split.next().unwrap().parse()?;
-        let length: u64 = split.next().unwrap().parse()?;
+        let mut next = || {
+            split
+                .next()
+                .ok_or_else(|| anyhow!("malformed range map line: {s}"))
+        };
+        let dest_start = next()?.parse()?;
+        let source_start = next()?.parse()?;
+        let length: u64 = next()?.parse()?;
 
         Ok(RangeMap {
             dest: dest_start..(dest_start + length),
@@ -47,9 +45,38 @@ impl FromStr for RangeMap {
     }
 }
 
+/// Prints the map in the almanac's `dest src len` form, so it parses back to itself. A zero-length
+/// map prints as `dest src 0`, which parses back to the same empty ranges.
+impl Display for RangeMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.dest.start,
+            self.src.start,
+            self.src_len()
+        )
+    }
+}
+
 impl RangeMap {
+    /// Whether this map translates `value`.
+    pub fn covers(&self, value: u64) -> bool {
+        self.src.contains(&value)
+    }
+
+    /// How many values this map translates.
+    pub fn src_len(&self) -> u64 {
+        self.src.end - self.src.start
+    }
+
+    /// The part of `range` that this map translates, or `None` if it doesn't touch `src`.
+    pub fn intersect_src(&self, range: &Range<u64>) -> Option<Range<u64>> {
+        self.src.intersection(range)
+    }
+
     pub fn lookup(&self, value: u64) -> Option<u64> {
-        if self.src.contains(&value) {
+        if self.covers(value) {
             let offset = value - self.src.start;
             Some(self.dest.start + offset)
         } else {
@@ -57,66 +84,64 @@ impl RangeMap {
         }
     }
 
-    // TODO: Go through all of the RangeMaps, and only the leftover ranges don't get mapped.
-    // This should be Some((overlap, leftover)) or None if there is no overlap.
-    pub fn map_onto(&self, input: Range<u64>) -> Option<Vec<Range<u64>>> {
-        if !self.src.overlaps(&input) {
-            return None;
+    /// The inverse map, translating from `dest` back to `src`.
+    pub fn reverse(&self) -> RangeMap {
+        RangeMap {
+            dest: self.src.clone(),
+            src: self.dest.clone(),
         }
+    }
 
-        // 4 cases of overlap:
-        //
-        //  * input within self
-        //  * self within input
-        //  * self starts before input
-        //  * input start before self
-
-        let mut results = if self.src.start <= input.start && self.src.end >= input.end {
-            // input within self
-            // Map[[start .... input_start...input_end...end]] -> map(input_start)..map(input_end)
-            let start_diff = input.start.saturating_sub(self.src.start);
-            let end_diff = self.src.end.saturating_sub(input.end);
-            let start = self.dest.start + start_diff;
-            let end = self.dest.end - end_diff;
-
-            #[allow(clippy::single_range_in_vec_init)]
-            {
-                vec![start..end]
-            }
-        } else if self.src.start >= input.start && self.src.end <= input.end {
-            // self within input.
-            // input_start...Map[[start...end]]...input_end -> [input_start..src_start] [map(src_start)...map(src_end)] [src...input_end]
-            let front = input.start..self.src.start;
-            let middle = self.dest.start..self.dest.end;
-            let end = self.src.end..input.end;
-
-            vec![front, middle, end]
-        } else if self.src.start >= input.start && self.src.end >= input.end {
-            // input start before self
-            let front = input.start..self.src.start;
-            let end = {
-                let diff = input.end - self.src.start;
-                self.dest.start..(self.dest.start + diff)
-            };
-            vec![front, end]
-        } else if self.src.start <= input.start && self.src.end <= input.end {
-            // self starts before input
-            let front = {
-                let diff = self.src.end - input.start;
-                (self.dest.end - diff)..self.dest.end
-            };
-            let end = self.src.end..input.end;
-            vec![front, end]
-        } else {
-            unreachable!("Should have return if there was no overlap")
-        };
+    pub fn reverse_lookup(&self, value: u64) -> Option<u64> {
+        self.reverse().lookup(value)
+    }
 
-        results.sort_by(|a, b| a.start.cmp(&b.start));
-        Some(results.merge_overlap())
+    /// Collapses this map and `next` into a single map, covering only the values this map
+    /// sends into `next.src`. Returns `None` if `self.dest` and `next.src` are disjoint.
+    pub fn compose(&self, next: &RangeMap) -> Option<RangeMap> {
+        let overlap = self.dest.intersection(&next.src)?;
+
+        let len = overlap.end - overlap.start;
+        let src_start = self.src.start + (overlap.start - self.dest.start);
+        let dest_start = next.dest.start + (overlap.start - next.src.start);
+
+        Some(RangeMap {
+            dest: dest_start..(dest_start + len),
+            src: src_start..(src_start + len),
+        })
+    }
+
+    /// Maps the part of `input` covered by `src` onto `dest`.
+    ///
+    /// Returns `(mapped, leftover)`, where `mapped` holds the translated ranges and `leftover`
+    /// holds the parts of `input` that this map doesn't cover, untouched. Without any overlap,
+    /// `mapped` is empty and `leftover` is just `input`.
+    pub fn apply_range(&self, input: &Range<u64>) -> MappedRanges {
+        let mapped = self
+            .intersect_src(input)
+            .map(|overlap| {
+                let start = self.dest.start + (overlap.start - self.src.start);
+                start..start + (overlap.end - overlap.start)
+            })
+            .into_iter()
+            .collect();
+
+        (mapped, input.difference(&self.src))
+    }
+
+    /// Same as `apply_range`, but returns `None` if there is no overlap.
+    pub fn map_onto(&self, input: Range<u64>) -> Option<MappedRanges> {
+        let (mapped, leftover) = self.apply_range(&input);
+        if mapped.is_empty() {
+            None
+        } else {
+            Some((mapped, leftover))
+        }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod range_map_tests {
     use super::*;
     use anyhow::Result;
@@ -135,110 +160,333 @@ mod range_map_tests {
     }
 
     #[test]
-    fn test_map_onto() -> Result<()> {
+    fn test_covers() -> Result<()> {
+        let range_map: RangeMap = "50 98 2".parse()?;
+        assert!(!range_map.covers(97));
+        assert!(range_map.covers(98));
+        assert!(range_map.covers(99));
+        assert!(!range_map.covers(100));
+        Ok(())
+    }
+
+    #[test]
+    fn test_src_len() -> Result<()> {
+        assert_eq!("50 98 2".parse::<RangeMap>()?.src_len(), 2);
+        assert_eq!("50 98 0".parse::<RangeMap>()?.src_len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_intersect_src() -> Result<()> {
+        let range_map: RangeMap = "52 50 48".parse()?;
+        assert_eq!(range_map.intersect_src(&(0..50)), None);
+        assert_eq!(range_map.intersect_src(&(0..51)), Some(50..51));
+        assert_eq!(range_map.intersect_src(&(60..70)), Some(60..70));
+        assert_eq!(range_map.intersect_src(&(97..120)), Some(97..98));
+        assert_eq!(range_map.intersect_src(&(98..120)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let err = "50 98".parse::<RangeMap>().unwrap_err();
+        assert_eq!(err.to_string(), "malformed range map line: 50 98");
+
+        let err = "".parse::<RangeMap>().unwrap_err();
+        assert_eq!(err.to_string(), "malformed range map line: ");
+
+        assert!("50 x 2".parse::<RangeMap>().is_err());
+        assert!("50 98 -2".parse::<RangeMap>().is_err());
+    }
+
+    #[test]
+    fn test_almanac_with_malformed_map() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98\n";
+        assert!(input.parse::<Almanac>().is_err());
+    }
+
+    #[test]
+    fn test_display_roundtrip() -> Result<()> {
+        let range_map: RangeMap = "50 98 2".parse()?;
+        assert_eq!(range_map.to_string(), "50 98 2");
+        assert_eq!(range_map.to_string().parse::<RangeMap>()?, range_map);
+
+        let empty = RangeMap {
+            dest: 7..7,
+            src: 3..3,
+        };
+        assert_eq!(empty.to_string(), "7 3 0");
+        assert_eq!(empty.to_string().parse::<RangeMap>()?, empty);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_lookup() -> Result<()> {
+        let range_map: RangeMap = "0 10 10".parse()?;
+        assert_eq!(range_map.reverse().src, 0..10);
+        assert_eq!(range_map.reverse().dest, 10..20);
+        assert_eq!(range_map.reverse_lookup(0), Some(10));
+        assert_eq!(range_map.reverse_lookup(9), Some(19));
+        assert_eq!(range_map.reverse_lookup(10), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose() -> Result<()> {
+        let range_map: RangeMap = "50 98 2".parse()?;
+        let shift: RangeMap = "60 40 20".parse()?;
+
+        let composed = range_map.compose(&shift).unwrap();
+        assert_eq!(composed, "70 98 2".parse()?);
+        for value in 98..100 {
+            let sequential = range_map.lookup(value).and_then(|v| shift.lookup(v));
+            assert_eq!(composed.lookup(value), sequential);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_partial_overlap() -> Result<()> {
+        let range_map: RangeMap = "50 98 2".parse()?;
+        let next: RangeMap = "0 51 5".parse()?;
+        assert_eq!(range_map.compose(&next), Some("0 99 1".parse()?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_disjoint() -> Result<()> {
+        let range_map: RangeMap = "50 98 2".parse()?;
+        let next: RangeMap = "0 52 10".parse()?;
+        assert_eq!(range_map.compose(&next), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_range_no_overlap() -> Result<()> {
+        let range_map: RangeMap = "0 10 10".parse()?;
+        assert_eq!(range_map.apply_range(&(1..5)), (vec![], vec![1..5]));
+        assert_eq!(range_map.apply_range(&(20..25)), (vec![], vec![20..25]));
+        assert_eq!(range_map.apply_range(&(12..12)), (vec![], vec![]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_range_full_cover() -> Result<()> {
+        let range_map: RangeMap = "0 10 10".parse()?;
+        assert_eq!(range_map.apply_range(&(10..20)), (vec![0..10], vec![]));
+        assert_eq!(range_map.apply_range(&(12..15)), (vec![2..5], vec![]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_range_partial() -> Result<()> {
+        let range_map: RangeMap = "0 10 10".parse()?;
+        assert_eq!(range_map.apply_range(&(5..15)), (vec![0..5], vec![5..10]));
+        assert_eq!(
+            range_map.apply_range(&(5..25)),
+            (vec![0..10], vec![5..10, 20..25])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_onto_no_overlap() -> Result<()> {
         let range_map: RangeMap = "10 20 10".parse()?;
         assert_eq!(range_map.map_onto(1..5), None);
-        assert_eq!(range_map.map_onto(22..28), Some(vec![12..18]));
-        assert_eq!(range_map.map_onto(8..32), Some(vec![8..20, 30..32]));
-        assert_eq!(range_map.map_onto(18..22), Some(vec![10..12, 18..20]));
-        assert_eq!(range_map.map_onto(28..32), Some(vec![18..20, 30..32]));
+        assert_eq!(range_map.map_onto(15..20), None);
+        assert_eq!(range_map.map_onto(30..32), None);
         Ok(())
     }
-}
 
-#[derive(Clone, Debug, Default)]
-pub struct Almanac {
-    seeds: Vec<u64>,
-    seed_to_soil_map: Vec<RangeMap>,
-    soil_to_fertilizer_map: Vec<RangeMap>,
-    fertilizer_to_water_map: Vec<RangeMap>,
-    water_to_light_map: Vec<RangeMap>,
-    light_to_temperature_map: Vec<RangeMap>,
-    temperature_to_humidity_map: Vec<RangeMap>,
-    humidity_to_location_map: Vec<RangeMap>,
+    #[test]
+    fn test_map_onto_input_within_self() -> Result<()> {
+        let range_map: RangeMap = "10 20 10".parse()?;
+        assert_eq!(range_map.map_onto(22..28), Some((vec![12..18], vec![])));
+        assert_eq!(range_map.map_onto(20..30), Some((vec![10..20], vec![])));
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_onto_self_within_input() -> Result<()> {
+        let range_map: RangeMap = "10 20 10".parse()?;
+        assert_eq!(
+            range_map.map_onto(8..32),
+            Some((vec![10..20], vec![8..20, 30..32]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_onto_input_starts_before_self() -> Result<()> {
+        let range_map: RangeMap = "10 20 10".parse()?;
+        assert_eq!(
+            range_map.map_onto(18..22),
+            Some((vec![10..12], vec![18..20]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_onto_self_starts_before_input() -> Result<()> {
+        let range_map: RangeMap = "10 20 10".parse()?;
+        assert_eq!(
+            range_map.map_onto(28..32),
+            Some((vec![18..20], vec![30..32]))
+        );
+        Ok(())
+    }
 }
 
-#[derive(Default)]
-enum ParseState {
-    #[default]
-    Seeds,
-    SeedToSoilMap,
-    SoilToFertilizerMap,
-    FertilizerToWaterMap,
-    WaterToLightMap,
-    LightToTemperatureMap,
-    TemperatureToHumidityMap,
-    HumidityToLocationMap,
-    Done,
+#[cfg(test)]
+mod map_onto_props {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn range_map() -> impl Strategy<Value = RangeMap> {
+        (0..1000u64, 0..1000u64, 1..200u64).prop_map(|(dest, src, len)| RangeMap {
+            dest: dest..dest + len,
+            src: src..src + len,
+        })
+    }
+
+    fn input() -> impl Strategy<Value = Range<u64>> {
+        (0..1200u64, 0..300u64).prop_map(|(start, len)| start..start + len)
+    }
+
+    proptest! {
+        #[test]
+        fn keeps_every_value(range_map in range_map(), input in input()) {
+            match range_map.map_onto(input.clone()) {
+                None => prop_assert!(input.clone().all(|v| range_map.lookup(v).is_none())),
+                Some((mapped, leftover)) => {
+                    let len = |ranges: &[Range<u64>]| ranges.iter().map(|r| r.end - r.start).sum::<u64>();
+                    prop_assert_eq!(len(&mapped) + len(&leftover), input.end - input.start);
+
+                    let mut values = mapped
+                        .iter()
+                        .flat_map(|r| r.clone())
+                        .map(|v| range_map.reverse_lookup(v))
+                        .collect::<Option<Vec<_>>>()
+                        .expect("mapped values come from dest");
+                    values.extend(leftover.iter().flat_map(|r| r.clone()));
+                    values.sort_unstable();
+                    prop_assert_eq!(values, input.collect::<Vec<_>>());
+                }
+            }
+        }
+
+        #[test]
+        fn mapped_values_are_offset(range_map in range_map(), input in input()) {
+            if let Some((mapped, leftover)) = range_map.map_onto(input.clone()) {
+                let overlap = range_map.src.intersection(&input).unwrap();
+                let expected = overlap
+                    .map(|v| range_map.dest.start + (v - range_map.src.start))
+                    .collect::<Vec<_>>();
+                let actual = mapped.iter().flat_map(|r| r.clone()).collect::<Vec<_>>();
+                prop_assert_eq!(actual, expected);
+
+                for v in leftover.iter().flat_map(|r| r.clone()) {
+                    prop_assert!(input.contains(&v));
+                    prop_assert!(!range_map.src.contains(&v));
+                }
+                if range_map.src.contains_range(&input) {
+                    prop_assert!(leftover.is_empty());
+                }
+                if input.contains_range(&range_map.src) {
+                    prop_assert_eq!(mapped, vec![range_map.dest.clone()]);
+                }
+            }
+        }
+    }
 }
 
-impl ParseState {
-    pub fn next_category(&mut self) {
-        *self = match self {
-            ParseState::Seeds => ParseState::SeedToSoilMap,
-            ParseState::SeedToSoilMap => ParseState::SoilToFertilizerMap,
-            ParseState::SoilToFertilizerMap => ParseState::FertilizerToWaterMap,
-            ParseState::FertilizerToWaterMap => ParseState::WaterToLightMap,
-            ParseState::WaterToLightMap => ParseState::LightToTemperatureMap,
-            ParseState::LightToTemperatureMap => ParseState::TemperatureToHumidityMap,
-            ParseState::TemperatureToHumidityMap => ParseState::HumidityToLocationMap,
-            ParseState::HumidityToLocationMap => ParseState::Done,
-            ParseState::Done => ParseState::Done,
+#[cfg(test)]
+mod merge_overlap_props {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    fn ranges() -> impl Strategy<Value = Vec<Range<u64>>> {
+        prop::collection::vec((0..200u64, 0..30u64).prop_map(|(s, len)| s..s + len), 0..20)
+    }
+
+    proptest! {
+        #[test]
+        fn is_canonical(ranges in ranges()) {
+            let merged = ranges.merge_overlap();
+
+            prop_assert!(merged.iter().all(|r| !r.is_empty()));
+            for pair in merged.windows(2) {
+                // sorted, and not even touching
+                prop_assert!(pair[0].end < pair[1].start);
+            }
         }
+
+        #[test]
+        fn covers_the_same_values(ranges in ranges()) {
+            let merged = ranges.merge_overlap();
+
+            let before = ranges.iter().flat_map(|r| r.clone()).collect::<BTreeSet<_>>();
+            let after = merged.iter().flat_map(|r| r.clone()).collect::<BTreeSet<_>>();
+            prop_assert_eq!(before, after);
+        }
+
+        #[test]
+        fn counts_every_non_empty_range(ranges in ranges()) {
+            let counted = ranges.merge_overlap_counted();
+
+            let total = counted.iter().map(|(_, count)| count).sum::<usize>();
+            prop_assert_eq!(total, ranges.iter().filter(|r| !r.is_empty()).count());
+            let merged = counted.into_iter().map(|(range, _)| range).collect::<Vec<_>>();
+            prop_assert_eq!(merged, ranges.merge_overlap());
+        }
+
+        #[test]
+        fn is_idempotent(ranges in ranges()) {
+            let merged = ranges.merge_overlap();
+            prop_assert_eq!(merged.merge_overlap(), merged);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn test_unsorted_and_chained() {
+        // 0..5 only reaches 8..12 through 4..9, which comes last.
+        let ranges = vec![8..12, 0..5, 20..20, 4..9];
+        assert_eq!(ranges.merge_overlap(), vec![0..12]);
     }
 }
 
+/// The seeds, followed by each category's range maps in the order they appear in the input.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    maps: Vec<Vec<RangeMap>>,
+}
+
 impl FromStr for Almanac {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut state = ParseState::default();
         let mut almanac = Almanac::default();
-        let mut skip = false;
 
         for line in s.lines().map(str::trim) {
-            if skip {
-                skip = false;
-                continue;
-            }
-
             if line.is_empty() {
-                state.next_category();
-                // skip the header of the next category
-                skip = true;
                 continue;
             }
 
-            match state {
-                ParseState::Seeds => {
-                    almanac.seeds = line[6..]
-                        .split_whitespace()
-                        .map(str::parse)
-                        .map(Result::unwrap)
-                        .collect();
-                }
-                ParseState::SeedToSoilMap => {
-                    almanac.seed_to_soil_map.push(line.parse()?);
-                }
-                ParseState::SoilToFertilizerMap => {
-                    almanac.soil_to_fertilizer_map.push(line.parse()?);
-                }
-                ParseState::FertilizerToWaterMap => {
-                    almanac.fertilizer_to_water_map.push(line.parse()?);
-                }
-                ParseState::WaterToLightMap => {
-                    almanac.water_to_light_map.push(line.parse()?);
-                }
-                ParseState::LightToTemperatureMap => {
-                    almanac.light_to_temperature_map.push(line.parse()?);
-                }
-                ParseState::TemperatureToHumidityMap => {
-                    almanac.temperature_to_humidity_map.push(line.parse()?);
-                }
-                ParseState::HumidityToLocationMap => {
-                    almanac.humidity_to_location_map.push(line.parse()?);
-                }
-                ParseState::Done => return Ok(almanac),
+            if line.starts_with("seeds:") {
+                almanac.seeds = parse_labeled_numbers(line, "seeds:")?;
+            } else if line.ends_with("map:") {
+                // every "x-to-y map:" header starts a new category
+                almanac.maps.push(vec![]);
+            } else {
+                almanac
+                    .maps
+                    .last_mut()
+                    .ok_or_else(|| anyhow!("Range map found before a map header: {line}"))?
+                    .push(line.parse()?);
             }
         }
 
@@ -247,100 +495,204 @@ impl FromStr for Almanac {
 }
 
 impl Almanac {
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    pub fn seed_count(&self) -> usize {
+        self.seeds.len()
+    }
+
+    /// How many seeds have to be sent through the categories one at a time, which for part 1 is
+    /// every listed seed. Compare with `Almanac2::work_estimate`.
+    pub fn work_estimate(&self) -> u64 {
+        self.seeds.len() as u64
+    }
+
+    pub fn maps(&self) -> &[Vec<RangeMap>] {
+        &self.maps
+    }
+
+    /// Checks that no two maps in a category translate the same source value. Real almanacs never
+    /// do, but an edited one could, and then a value would be mapped twice.
+    pub fn validate(&self) -> Result<(), Error> {
+        for (category, maps) in self.maps.iter().enumerate() {
+            let mut sorted = maps.iter().collect::<Vec<_>>();
+            sorted.sort_by_key(|map| map.src.start);
+
+            for pair in sorted.windows(2) {
+                if pair[0].src.overlaps(&pair[1].src) {
+                    return Err(anyhow!(
+                        "Overlapping maps in category {}: \"{}\" and \"{}\"",
+                        category + 1,
+                        pair[0],
+                        pair[1]
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn lowest_location_that_needs_a_seed(&self) -> u64 {
         self.seeds
             .iter()
             .map(|&seed| {
-                self.seed_to_soil_map
+                self.maps
                     .iter()
-                    .map(|map| map.lookup(seed))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(seed)
-            })
-            .map(|soil| {
-                self.soil_to_fertilizer_map
-                    .iter()
-                    .map(|map| map.lookup(soil))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(soil)
-            })
-            .map(|fertilizer| {
-                self.fertilizer_to_water_map
-                    .iter()
-                    .map(|map| map.lookup(fertilizer))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(fertilizer)
-            })
-            .map(|water| {
-                self.water_to_light_map
-                    .iter()
-                    .map(|map| map.lookup(water))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(water)
-            })
-            .map(|light| {
-                self.light_to_temperature_map
-                    .iter()
-                    .map(|map| map.lookup(light))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(light)
-            })
-            .map(|temperature| {
-                self.temperature_to_humidity_map
-                    .iter()
-                    .map(|map| map.lookup(temperature))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(temperature)
-            })
-            .map(|humidity| {
-                self.humidity_to_location_map
-                    .iter()
-                    .map(|map| map.lookup(humidity))
-                    .fold(None, |a, b| a.or(b))
-                    .unwrap_or(humidity)
+                    .fold(seed, |value, maps| apply_maps(value, maps))
             })
             .min()
             .unwrap()
     }
+
+    /// The seed followed by its value after each category, ending with its location. A `Vec`
+    /// rather than a fixed array because the number of categories comes from the input, so the
+    /// length is one more than the number of categories (8 for the puzzle's seven).
+    pub fn trace(&self, seed: u64) -> Vec<u64> {
+        let stages = self.maps.iter().scan(seed, |value, maps| {
+            *value = apply_maps(*value, maps);
+            Some(*value)
+        });
+        std::iter::once(seed).chain(stages).collect()
+    }
+
+    /// Composes every category into one list of seed-to-location maps, so a seed can be looked up
+    /// with a single `apply_maps` instead of one per category. Seeds outside every map keep their
+    /// value, as in each category. Assumes the almanac passes `validate`.
+    pub fn flatten(&self) -> Vec<RangeMap> {
+        let identity = |src: Range<u64>| RangeMap {
+            dest: src.clone(),
+            src,
+        };
+
+        let composed = self
+            .maps
+            .iter()
+            .fold(vec![identity(0..u64::MAX)], |composed, maps| {
+                // Fill the gaps between the category's maps with identity maps, so their sources
+                // cover every value and each composed map is split without losing any part of it.
+                let mut sorted = maps.clone();
+                sorted.sort_by_key(|map| map.src.start);
+                let mut next = vec![];
+                let mut covered = 0;
+                for map in sorted {
+                    if covered < map.src.start {
+                        next.push(identity(covered..map.src.start));
+                    }
+                    covered = covered.max(map.src.end);
+                    next.push(map);
+                }
+                if covered < u64::MAX {
+                    next.push(identity(covered..u64::MAX));
+                }
+
+                composed
+                    .iter()
+                    .flat_map(|map| next.iter().filter_map(|next| map.compose(next)))
+                    .collect()
+            });
+
+        composed
+            .into_iter()
+            .filter(|map| map.src != map.dest)
+            .collect()
+    }
+
+    /// Finds the lowest seed from the seed list that ends up at `location`.
+    ///
+    /// Walks the categories backwards. A value can have several sources in a category: any map
+    /// whose `dest` covers it, plus the value itself when no map's `src` covers it.
+    pub fn seed_for_location(&self, location: u64) -> Option<u64> {
+        let candidates = self.maps.iter().rev().fold(vec![location], |values, maps| {
+            values
+                .iter()
+                .flat_map(|&value| {
+                    let mut sources: Vec<u64> = maps
+                        .iter()
+                        .filter_map(|map| map.reverse_lookup(value))
+                        .collect();
+                    if !maps.iter().any(|map| map.src.contains(&value)) {
+                        sources.push(value);
+                    }
+                    sources
+                })
+                .collect()
+        });
+
+        candidates
+            .into_iter()
+            .filter(|seed| self.seeds.contains(seed))
+            .min()
+    }
+}
+
+/// Builds an [`Almanac`] directly, without writing it out as text first.
+#[derive(Clone, Debug, Default)]
+pub struct AlmanacBuilder {
+    almanac: Almanac,
+}
+
+impl AlmanacBuilder {
+    pub fn seeds(mut self, seeds: &[u64]) -> Self {
+        self.almanac.seeds = seeds.to_vec();
+        self
+    }
+
+    /// Adds a category after the existing ones, from `(dest, src, len)` triples.
+    pub fn category(mut self, maps: &[(u64, u64, u64)]) -> Self {
+        let maps = maps
+            .iter()
+            .map(|&(dest, src, len)| RangeMap {
+                dest: dest..dest + len,
+                src: src..src + len,
+            })
+            .collect();
+        self.almanac.maps.push(maps);
+        self
+    }
+
+    pub fn build(self) -> Almanac {
+        self.almanac
+    }
 }
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Almanac2 {
     seeds: Vec<Range<u64>>,
-    seed_to_soil_map: Vec<RangeMap>,
-    soil_to_fertilizer_map: Vec<RangeMap>,
-    fertilizer_to_water_map: Vec<RangeMap>,
-    water_to_light_map: Vec<RangeMap>,
-    light_to_temperature_map: Vec<RangeMap>,
-    temperature_to_humidity_map: Vec<RangeMap>,
-    humidity_to_location_map: Vec<RangeMap>,
+    maps: Vec<Vec<RangeMap>>,
 }
 
-impl From<Almanac> for Almanac2 {
-    fn from(value: Almanac) -> Self {
+impl TryFrom<Almanac> for Almanac2 {
+    type Error = Error;
+
+    /// Reads the seeds as `start length` pairs, so there has to be an even number of them.
+    fn try_from(value: Almanac) -> Result<Self, Self::Error> {
+        if !value.seeds.len().is_multiple_of(2) {
+            return Err(anyhow!(
+                "Expected seed ranges as start and length pairs, found {} seed values",
+                value.seeds.len()
+            ));
+        }
+
         let mut seeds: Vec<Range<u64>> = value
             .seeds
-            .windows(2)
-            .step_by(2)
-            .map(|window| {
-                let start = window[0];
-                let end = start + window[1];
+            .chunks_exact(2)
+            .map(|pair| {
+                let start = pair[0];
+                let end = start + pair[1];
 
                 start..end
             })
             .collect();
-        seeds.sort_by(|a, b| a.start.cmp(&b.start));
+        seeds.sort_by_key(|a| a.start);
 
-        Almanac2 {
+        Ok(Almanac2 {
             seeds,
-            seed_to_soil_map: value.seed_to_soil_map,
-            soil_to_fertilizer_map: value.soil_to_fertilizer_map,
-            fertilizer_to_water_map: value.fertilizer_to_water_map,
-            water_to_light_map: value.water_to_light_map,
-            light_to_temperature_map: value.light_to_temperature_map,
-            temperature_to_humidity_map: value.temperature_to_humidity_map,
-            humidity_to_location_map: value.humidity_to_location_map,
-        }
+            maps: value.maps,
+        })
     }
 }
 
@@ -349,54 +701,99 @@ impl FromStr for Almanac2 {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let almanac: Almanac = s.parse()?;
-        Ok(almanac.into())
+        almanac.try_into()
     }
 }
 
 impl Almanac2 {
-    pub fn maps(&self) -> Vec<Vec<RangeMap>> {
-        vec![
-            self.seed_to_soil_map.clone(),
-            self.soil_to_fertilizer_map.clone(),
-            self.fertilizer_to_water_map.clone(),
-            self.water_to_light_map.clone(),
-            self.light_to_temperature_map.clone(),
-            self.temperature_to_humidity_map.clone(),
-            self.humidity_to_location_map.clone(),
-        ]
+    /// The seed ranges, sorted by start.
+    pub fn seed_ranges(&self) -> &[Range<u64>] {
+        &self.seeds
+    }
+
+    /// How many seeds the ranges hold in total.
+    pub fn total_seed_count(&self) -> u64 {
+        self.seeds.iter().map(|range| range.end - range.start).sum()
+    }
+
+    /// How many seeds brute force would have to send through the categories one at a time, which
+    /// for part 2 is every seed in every range. `lowest_location_that_needs_a_seed` works on whole
+    /// ranges instead, so its cost depends on how often the ranges split, not on this.
+    pub fn work_estimate(&self) -> u64 {
+        self.total_seed_count()
+    }
+
+    pub fn maps(&self) -> &[Vec<RangeMap>] {
+        &self.maps
     }
 
     pub fn lowest_location_that_needs_a_seed(&self) -> u64 {
-        let mut locations = self.maps().iter().fold(
-            self.seeds.clone(),
-            move |ranges: Vec<Range<u64>>, maps: &Vec<RangeMap>| {
-                ranges
-                    .iter()
-                    .flat_map(move |range| {
-                        let mut results = maps
-                            .iter()
-                            .filter_map(move |map| map.map_onto(range.clone()))
-                            .flatten()
-                            .collect::<Vec<_>>();
-
-                        if results.is_empty() {
-                            vec![range.clone()]
-                        } else {
-                            results.sort_by(|a, b| a.start.cmp(&b.start));
-                            results.merge_overlap()
-                        }
-                    })
-                    .collect()
-            },
-        );
-        locations.sort_by(|a, b| a.start.cmp(&b.start));
+        self.location_ranges()[0].start
+    }
+
+    /// Every location some seed ends up at, as merged ranges sorted by start.
+    ///
+    /// Each seed range is sent through the categories independently on rayon's global thread pool,
+    /// which has one thread per logical CPU unless `RAYON_NUM_THREADS` says otherwise. The results
+    /// are merged afterwards, so they don't depend on scheduling.
+    pub fn location_ranges(&self) -> Vec<Range<u64>> {
+        self.seeds
+            .par_iter()
+            .flat_map_iter(|seeds| self.locations(vec![seeds.clone()]))
+            .collect::<Vec<_>>()
+            .merge_overlap()
+    }
 
-        locations
+    // The sorted, merged location ranges reachable from `seeds`.
+    fn locations(&self, seeds: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        self.maps
             .iter()
-            .filter(|x| x.start != 0)
-            .map(|range| range.start)
-            .min()
-            .unwrap()
+            .fold(seeds, |ranges, maps| map_ranges(maps, ranges))
+    }
+}
+
+// Sends a value through the first map that covers it, or passes it through unchanged.
+fn apply_maps(value: u64, maps: &[RangeMap]) -> u64 {
+    maps.iter()
+        .find_map(|map| map.lookup(value))
+        .unwrap_or(value)
+}
+
+// Sends each range through the first map that covers it. Whatever no map covers is passed through unchanged.
+fn map_ranges(maps: &[RangeMap], ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    let (mut mapped, leftover) = maps.iter().fold(
+        (vec![], ranges),
+        |(mut mapped, unmapped): MappedRanges, map| {
+            let mut leftover = vec![];
+            for range in unmapped {
+                let (m, l) = map.apply_range(&range);
+                mapped.extend(m);
+                leftover.extend(l);
+            }
+            (mapped, leftover)
+        },
+    );
+
+    mapped.extend(leftover);
+    mapped.merge_overlap()
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = Almanac;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        input.parse()
+    }
+
+    fn part1(almanac: &Self::Parsed) -> anyhow::Result<String> {
+        Ok(almanac.lowest_location_that_needs_a_seed().to_string())
+    }
+
+    fn part2(almanac: &Self::Parsed) -> anyhow::Result<String> {
+        let almanac: Almanac2 = almanac.clone().try_into()?;
+        Ok(almanac.lowest_location_that_needs_a_seed().to_string())
     }
 }
 
@@ -447,12 +844,246 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_builder() -> Result<()> {
+        let almanac = AlmanacBuilder::default()
+            .seeds(&[79, 14, 55, 13])
+            .category(&[(50, 98, 2), (52, 50, 48)])
+            .category(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)])
+            .category(&[(49, 53, 8), (0, 11, 42), (42, 0, 7), (57, 7, 4)])
+            .category(&[(88, 18, 7), (18, 25, 70)])
+            .category(&[(45, 77, 23), (81, 45, 19), (68, 64, 13)])
+            .category(&[(0, 69, 1), (1, 0, 69)])
+            .category(&[(60, 56, 37), (56, 93, 4)])
+            .build();
+
+        assert_eq!(almanac.lowest_location_that_needs_a_seed(), 35);
+        assert_eq!(almanac, INPUT.parse::<Almanac>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> Result<()> {
+        let almanac: Almanac = INPUT.parse()?;
+        assert_eq!(almanac.trace(79).len(), 8);
+        assert_eq!(almanac.trace(79), vec![79, 81, 81, 81, 74, 78, 78, 82]);
+        assert_eq!(almanac.trace(13).last(), Some(&35));
+        Ok(())
+    }
+
+    #[test]
+    fn test_flatten() -> Result<()> {
+        let almanac: Almanac = INPUT.parse()?;
+        let flat = almanac.flatten();
+
+        for &seed in almanac.seeds() {
+            assert_eq!(Some(&apply_maps(seed, &flat)), almanac.trace(seed).last());
+        }
+        for value in (0..200).chain([u64::MAX - 1]) {
+            assert_eq!(
+                Some(&apply_maps(value, &flat)),
+                almanac.trace(value).last(),
+                "{value}"
+            );
+        }
+
+        // Values that never move don't need a map.
+        let flat = AlmanacBuilder::default().category(&[]).build().flatten();
+        assert_eq!(flat, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flatten_splits_ranges() -> Result<()> {
+        let almanac = AlmanacBuilder::default()
+            .category(&[(100, 0, 10)])
+            .category(&[(200, 105, 10)])
+            .build();
+        let mut flat = almanac.flatten();
+        flat.sort_by_key(|map| map.src.start);
+
+        // 0..5 lands on 100..105, which the second category doesn't cover, but 5..10 lands on
+        // 105..110 and moves again. 105..115 only moves in the second category.
+        assert_eq!(
+            flat.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["100 0 5", "200 5 5", "200 105 10"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_seed_for_location() -> Result<()> {
+        let almanac: Almanac = INPUT.parse()?;
+        let location = almanac.lowest_location_that_needs_a_seed();
+        let seed = almanac.seed_for_location(location);
+        assert_eq!(seed, Some(13));
+        assert!(almanac.seeds.contains(&13));
+
+        assert_eq!(almanac.seed_for_location(82), Some(79));
+        assert_eq!(almanac.seed_for_location(36), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() -> Result<()> {
+        INPUT.parse::<Almanac>()?.validate()?;
+
+        let input = "seeds: 1 4
+
+                     seed-to-soil map:
+                     10 0 5
+
+                     soil-to-fertilizer map:
+                     0 20 10
+                     50 0 5
+                     70 25 3";
+        let err = input.parse::<Almanac>()?.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Overlapping maps in category 2: "0 20 10" and "70 25 3""#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeds() -> Result<()> {
+        let almanac: Almanac = INPUT.parse()?;
+        assert_eq!(almanac.seeds(), [79, 14, 55, 13]);
+        assert_eq!(almanac.seed_count(), 4);
+
+        assert_eq!(almanac.work_estimate(), 4);
+
+        let almanac: Almanac2 = almanac.try_into()?;
+        assert_eq!(almanac.seed_ranges(), [55..68, 79..93]);
+        assert_eq!(almanac.total_seed_count(), 27);
+        assert_eq!(almanac.work_estimate(), 27);
+        Ok(())
+    }
+
+    #[test]
+    fn test_odd_seed_count() -> Result<()> {
+        let almanac: Almanac = INPUT.replacen("13", "13 7", 1).parse()?;
+        assert_eq!(almanac.seed_count(), 5);
+
+        let err = Almanac2::try_from(almanac).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Expected seed ranges as start and length pairs, found 5 seed values"
+        );
+        assert!(INPUT.replacen("13", "13 7", 1).parse::<Almanac2>().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_part2() -> Result<()> {
         let almanac: Almanac2 = INPUT.parse()?;
         assert_eq!(almanac.lowest_location_that_needs_a_seed(), 46);
         Ok(())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() -> Result<()> {
+        let almanac: Almanac = INPUT.parse()?;
+        let json = serde_json::to_string(&almanac)?;
+        assert!(json.contains(r#"{"dest":{"start":50,"end":52},"src":{"start":98,"end":100}}"#));
+
+        let parsed: Almanac = serde_json::from_str(&json)?;
+        assert_eq!(parsed, almanac);
+        assert_eq!(parsed.lowest_location_that_needs_a_seed(), 35);
+
+        let almanac2: Almanac2 = INPUT.parse()?;
+        let json = serde_json::to_string(&almanac2)?;
+        let parsed: Almanac2 = serde_json::from_str(&json)?;
+        assert_eq!(parsed.seed_ranges(), almanac2.seed_ranges());
+        assert_eq!(parsed.lowest_location_that_needs_a_seed(), 46);
+        Ok(())
+    }
+
+    #[test]
+    fn test_any_number_of_categories() -> Result<()> {
+        let input = "seeds: 1 4
+
+                     seed-to-soil map:
+                     10 0 5
+
+                     soil-to-anything map:
+                     20 10 2";
+
+        let almanac: Almanac = input.parse()?;
+        assert_eq!(almanac.maps().len(), 2);
+        assert_eq!(almanac.lowest_location_that_needs_a_seed(), 14);
+
+        let almanac: Almanac2 = almanac.try_into()?;
+        assert_eq!(almanac.lowest_location_that_needs_a_seed(), 12);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lowest_location_is_zero() -> Result<()> {
+        let input = "seeds: 10 5
+
+                     seed-to-soil map:
+                     0 10 5
+
+                     soil-to-fertilizer map:
+                     100 200 10
+
+                     fertilizer-to-water map:
+                     100 200 10
+
+                     water-to-light map:
+                     100 200 10
+
+                     light-to-temperature map:
+                     100 200 10
+
+                     temperature-to-humidity map:
+                     100 200 10
+
+                     humidity-to-location map:
+                     100 200 10";
+
+        let almanac: Almanac = input.parse()?;
+        assert_eq!(almanac.lowest_location_that_needs_a_seed(), 0);
+
+        let almanac: Almanac2 = almanac.try_into()?;
+        assert_eq!(almanac.lowest_location_that_needs_a_seed(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_ranges() -> Result<()> {
+        let almanac: Almanac2 = INPUT.parse()?;
+        let ranges = almanac.location_ranges();
+        assert_eq!(ranges[0].start, 46);
+        assert_eq!(ranges, almanac.locations(almanac.seeds.clone()));
+        assert!(ranges.windows(2).all(|w| w[0].end < w[1].start));
+
+        // Every seed's location is in one of the ranges.
+        let part1: Almanac = INPUT.parse()?;
+        for seed in almanac.seed_ranges().iter().flat_map(|r| r.clone()) {
+            let location = *part1.trace(seed).last().unwrap();
+            assert!(ranges.iter().any(|r| r.contains(&location)), "{seed}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_part2_parallel_matches_serial() -> Result<()> {
+        let almanac: Almanac2 = INPUT.parse()?;
+        let serial = almanac.locations(almanac.seeds.clone())[0].start;
+        assert_eq!(almanac.lowest_location_that_needs_a_seed(), serial);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solution() -> Result<()> {
+        let almanac = Day5::parse(INPUT)?;
+        assert_eq!(Day5::part1(&almanac)?, "35");
+        assert_eq!(Day5::part2(&almanac)?, "46");
+        Ok(())
+    }
 }
 
 // 50 98 2 -> if src between 98 and 100, map it to 50 to 52. otherwise
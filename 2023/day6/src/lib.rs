@@ -0,0 +1,293 @@
+use anyhow::{anyhow, Result};
+use aoc_common::{normalize_lines, parse_labeled_numbers};
+use std::{ops::RangeInclusive, str::FromStr};
+
+/// This day's puzzle input, embedded when the binaries are built. `None` with the `fetch` feature,
+/// which reads (or downloads) `input.txt` at run time instead.
+#[cfg(not(feature = "fetch"))]
+pub const INPUT: Option<&str> = Some(include_str!("../input.txt"));
+#[cfg(feature = "fetch")]
+pub const INPUT: Option<&str> = None;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time_sec: u64,
+    pub dist_mm: u64,
+}
+
+impl From<(u64, u64)> for Race {
+    fn from(value: (u64, u64)) -> Self {
+        Self {
+            time_sec: value.0,
+            dist_mm: value.1,
+        }
+    }
+}
+
+impl Race {
+    /// The hold times that beat the record, or `None` if even the best hold can't.
+    pub fn winning_holds(&self) -> Option<RangeInclusive<u64>> {
+        lowest_winning_hold(self.time_sec, self.dist_mm).map(|low| low..=self.time_sec - low)
+    }
+
+    pub fn ways_to_beat_record(&self) -> u64 {
+        count_holds(self.time_sec, self.dist_mm, false)
+    }
+
+    /// The farthest the boat can go, by holding for half the race. Saturates at `u64::MAX` for
+    /// races too long for the distance to fit.
+    pub fn best_distance(&self) -> u64 {
+        let hold = self.time_sec / 2;
+        hold.saturating_mul(self.time_sec - hold)
+    }
+
+    /// The distance to beat.
+    pub fn record(&self) -> u64 {
+        self.dist_mm
+    }
+}
+
+// The numbers on the line that starts with `label`, which can be anywhere in the sheet.
+fn field<T: FromStr>(input: &str, label: &str) -> Result<Vec<T>>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let line = normalize_lines(input)
+        .find(|line| line.starts_with(label))
+        .ok_or_else(|| anyhow!("Missing {label} line"))?;
+    parse_labeled_numbers(line, label)
+}
+
+/// Reads each column of the sheet as its own race.
+pub fn parse_multi(input: &str) -> Result<Vec<Race>> {
+    let times = field::<u64>(input, "Time:")?;
+    let dists = field::<u64>(input, "Distance:")?;
+    if times.len() != dists.len() {
+        return Err(anyhow!(
+            "Found {} times but {} distances",
+            times.len(),
+            dists.len()
+        ));
+    }
+
+    Ok(times.into_iter().zip(dists).map(Into::into).collect())
+}
+
+/// Reads the sheet as a single race, ignoring the bad kerning between the digits.
+pub fn parse_kerned(input: &str) -> Result<Race> {
+    let time = field::<String>(input, "Time:")?.concat().parse()?;
+    let dist = field::<String>(input, "Distance:")?.concat().parse()?;
+
+    Ok((time, dist).into())
+}
+
+pub fn ways_to_beat_records(input: &str) -> Result<u64> {
+    Ok(parse_multi(input)?
+        .iter()
+        .map(Race::ways_to_beat_record)
+        .product())
+}
+
+/// Counts the hold times that beat the record without trying each one.
+pub fn ways_to_beat_record_fast(time: u64, dist: u64) -> u64 {
+    count_holds(time, dist, false)
+}
+
+/// Every hold time that beats the record, shortest first. This holds one entry per winning hold, so
+/// it's only meant for printing small races; the kerned race alone would need tens of millions.
+pub fn winning_hold_times(time: u64, dist: u64) -> Vec<u64> {
+    Race::from((time, dist))
+        .winning_holds()
+        .map_or_else(Vec::new, Iterator::collect)
+}
+
+/// Counts the hold times that beat the record, or with `inclusive` also the ones that tie it.
+pub fn count_holds(time: u64, dist: u64, inclusive: bool) -> u64 {
+    // Over integers, `d >= dist` is the same as `d > dist - 1`, and every hold ties a record of 0.
+    let dist = match (inclusive, dist.checked_sub(1)) {
+        (false, _) => dist,
+        (true, Some(dist)) => dist,
+        (true, None) => return time + 1,
+    };
+    lowest_winning_hold(time, dist).map_or(0, |low| time - 2 * low + 1)
+}
+
+/// Holding for `hold` ms travels `hold * (time - hold)` mm, so the winning holds lie strictly between
+/// the roots of `hold² - time·hold + dist = 0`. They're symmetric around `time / 2`, so once the
+/// lowest winning hold is known, the highest is `time - low`.
+fn lowest_winning_hold(time: u64, dist: u64) -> Option<u64> {
+    let beats = |hold: u64| hold as u128 * (time - hold) as u128 > dist as u128;
+
+    // The best hold is at the peak of the parabola. If that can't win, nothing can.
+    let peak = time / 2;
+    if !beats(peak) {
+        return None;
+    }
+
+    // Start from the float estimate of the lower root, then step to the first hold that strictly
+    // beats the record. A root that lands exactly on an integer is a tie, which doesn't count.
+    let disc = (time as f64).powi(2) - 4.0 * dist as f64;
+    let mut low = ((time as f64 - disc.max(0.0).sqrt()) / 2.0).max(0.0) as u64;
+    low = low.min(peak);
+    while !beats(low) {
+        low += 1;
+    }
+    while low > 0 && beats(low - 1) {
+        low -= 1;
+    }
+
+    Some(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "\
+    Time:      7  15   30
+    Distance:  9  40  200";
+
+    #[test]
+    fn test_parse_multi() -> Result<()> {
+        assert_eq!(
+            parse_multi(INPUT)?,
+            vec![(7, 9).into(), (15, 40).into(), (30, 200).into()]
+        );
+        assert!(parse_multi("Time: 7 15\nDistance: 9").is_err());
+        assert!(parse_multi("Time: 7 x\nDistance: 9 40").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_kerned() -> Result<()> {
+        assert_eq!(parse_kerned(INPUT)?, (71530, 940200).into());
+        Ok(())
+    }
+
+    #[test]
+    fn test_winning_holds() -> Result<()> {
+        let holds = parse_multi(INPUT)?
+            .iter()
+            .map(Race::winning_holds)
+            .collect::<Vec<_>>();
+        assert_eq!(holds, vec![Some(2..=5), Some(4..=11), Some(11..=19)]);
+
+        let race = Race::from((10, 25));
+        assert_eq!(race.winning_holds(), None);
+        assert_eq!(race.ways_to_beat_record(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_distance() {
+        // Holding for 3 or 4 of the 7ms both go 12mm.
+        let race = Race::from((7, 9));
+        assert_eq!(race.best_distance(), 12);
+        assert_eq!(race.record(), 9);
+
+        assert_eq!(Race::from((30, 200)).best_distance(), 225);
+        assert_eq!(Race::from((0, 0)).best_distance(), 0);
+        assert_eq!(Race::from((u64::MAX, 0)).best_distance(), u64::MAX);
+    }
+
+    #[test]
+    fn test_part1() -> Result<()> {
+        assert_eq!(ways_to_beat_records(INPUT)?, 288);
+        Ok(())
+    }
+
+    #[test]
+    fn test_crlf() -> Result<()> {
+        let crlf = INPUT.replace('\n', "\r\n");
+        assert_eq!(ways_to_beat_records(&crlf)?, 288);
+        assert_eq!(parse_kerned(&crlf)?.ways_to_beat_record(), 71503);
+        Ok(())
+    }
+
+    #[test]
+    fn test_part2() -> Result<()> {
+        assert_eq!(parse_kerned(INPUT)?.ways_to_beat_record(), 71503);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(ways_to_beat_record_fast(7, 9), 4);
+        assert_eq!(ways_to_beat_record_fast(15, 40), 8);
+        assert_eq!(ways_to_beat_record_fast(30, 200), 9);
+        assert_eq!(ways_to_beat_record_fast(71530, 940200), 71503);
+    }
+
+    #[test]
+    fn test_exact_roots() {
+        // 2 * 5 == 10 ties the record at both ends, leaving 3 and 4.
+        assert_eq!(ways_to_beat_record_fast(7, 10), 2);
+        // 4 * 6 == 24 ties, only 5 * 5 == 25 wins.
+        assert_eq!(ways_to_beat_record_fast(10, 24), 1);
+        // The peak itself only ties.
+        assert_eq!(ways_to_beat_record_fast(10, 25), 0);
+        assert_eq!(ways_to_beat_record_fast(0, 0), 0);
+    }
+
+    #[test]
+    fn test_winning_hold_times() {
+        assert_eq!(winning_hold_times(7, 9), vec![2, 3, 4, 5]);
+        assert_eq!(winning_hold_times(7, 10), vec![3, 4]);
+        assert_eq!(winning_hold_times(10, 25), vec![]);
+        assert_eq!(winning_hold_times(0, 0), vec![]);
+    }
+
+    #[test]
+    fn test_count_holds_inclusive() {
+        // Only the peak, 5 * 5 == 25, ties.
+        assert_eq!(count_holds(10, 25, false), 0);
+        assert_eq!(count_holds(10, 25, true), 1);
+        // 5 * 5 == 25 beats 24 and 4 * 6 == 24 ties at both ends.
+        assert_eq!(count_holds(10, 24, false), 1);
+        assert_eq!(count_holds(10, 24, true), 3);
+        // Nothing ties 9 in a 7ms race, so both agree.
+        assert_eq!(count_holds(7, 9, false), 4);
+        assert_eq!(count_holds(7, 9, true), 4);
+        // Any hold, even 0 or the whole race, ties a record of 0.
+        assert_eq!(count_holds(7, 0, true), 8);
+        assert_eq!(count_holds(0, 0, true), 1);
+    }
+
+    #[test]
+    fn test_count_holds_brute_force() {
+        for time in 0..40 {
+            for dist in 0..(time * time / 4 + 2) {
+                let brute = (0..=time)
+                    .filter(|&hold| hold * (time - hold) >= dist)
+                    .count() as u64;
+                assert_eq!(count_holds(time, dist, true), brute, "{time} {dist}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_huge_race() {
+        // Holding 2^31 ± x travels 2^62 - x², which beats 2^62 - 2^40 for |x| < 2^20.
+        let race = Race::from((1 << 32, (1 << 62) - (1 << 40)));
+        assert_eq!(race.ways_to_beat_record(), (1 << 21) - 1);
+        assert_eq!(
+            race.winning_holds(),
+            Some((1 << 31) - (1 << 20) + 1..=(1 << 31) + (1 << 20) - 1)
+        );
+
+        // Every hold but the two ends beats a record of 0.
+        assert_eq!(ways_to_beat_record_fast(u64::MAX, 0), u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for time in 0..60 {
+            for dist in 0..(time * time / 4 + 2) {
+                let brute = (1..time)
+                    .filter(|&hold| hold * (time - hold) > dist)
+                    .count() as u64;
+                assert_eq!(ways_to_beat_record_fast(time, dist), brute, "{time} {dist}");
+            }
+        }
+    }
+}
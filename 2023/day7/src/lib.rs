@@ -0,0 +1,761 @@
+use anyhow::{anyhow, Error, Result};
+use aoc_common::{counts, normalize_lines};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// This day's puzzle input, embedded when the binaries are built. `None` with the `fetch` feature,
+/// which reads (or downloads) `input.txt` at run time instead.
+#[cfg(not(feature = "fetch"))]
+pub const INPUT: Option<&str> = Some(include_str!("../input.txt"));
+#[cfg(feature = "fetch")]
+pub const INPUT: Option<&str> = None;
+
+#[derive(Clone, Debug)]
+pub struct Play {
+    pub hand: Hand,
+    pub bid: u64,
+}
+
+impl FromStr for Play {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with_order(s, CardOrder::Standard)
+    }
+}
+
+impl Play {
+    /// Parses a play whose hand is played under `order`.
+    pub fn parse_with_order(s: &str, order: CardOrder) -> Result<Self> {
+        let mut parts = s.split_whitespace();
+        let hand = Hand::parse_with_order(parts.next().ok_or(anyhow!("Missing hand"))?, order)?;
+        let bid = parts
+            .next()
+            .ok_or(anyhow!("Missing bid"))?
+            .parse()
+            .map_err(|_| anyhow!("Invalid bid"))?;
+
+        Ok(Self { hand, bid })
+    }
+}
+
+/// Five cards, in the order they were dealt.
+///
+/// Hands are ordered by type first, then card by card from the left. Equality is positional to
+/// match: two hands are equal only when they hold the same cards in the same order, so `a == b`
+/// exactly when `a.cmp(&b)` is `Equal`. `AATAA` and `AAAAT` hold the same cards but aren't equal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hand {
+    cards: Vec<Card>,
+    order: CardOrder,
+    // Computed once at parse time so sorting doesn't reclassify on every comparison.
+    hand_type: HandType,
+}
+
+impl FromStr for Hand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with_order(s, CardOrder::Standard)
+    }
+}
+
+impl Hand {
+    /// Parses a hand played under `order`, which decides whether `J` is a Jack or a wild Joker.
+    pub fn parse_with_order(s: &str, order: CardOrder) -> Result<Self> {
+        let cards = s.chars().map(Card::try_from).collect::<Result<Vec<_>>>()?;
+        if cards.len() != 5 {
+            return Err(anyhow!("Expected 5 cards, found {}", cards.len()));
+        }
+
+        let hand_type = HandType::from_cards(&cards, order);
+        Ok(Self {
+            cards,
+            order,
+            hand_type,
+        })
+    }
+
+    /// How many of each card the hand holds, most common first. Ties are in card order.
+    pub fn frequency(&self) -> Vec<(Card, usize)> {
+        frequency(&self.cards)
+    }
+
+    pub fn order(&self) -> CardOrder {
+        self.order
+    }
+
+    fn ranks(&self) -> impl Iterator<Item = u8> + '_ {
+        self.cards.iter().map(|card| card.rank(self.order))
+    }
+}
+
+impl Display for Hand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.cards
+            .iter()
+            .try_for_each(|&card| write!(f, "{}", char::from(card)))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Hands are only ever compared within one ordering, but falling back to it keeps `Ord`
+        // consistent with the derived `Eq`.
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.ranks().cmp(other.ranks()))
+            .then_with(|| self.order.cmp(&other.order))
+    }
+}
+
+#[cfg(test)]
+mod hand_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wrong_length() {
+        let err = "AAA".parse::<Hand>().unwrap_err();
+        assert_eq!(err.to_string(), "Expected 5 cards, found 3");
+
+        let err = "AAAAAA".parse::<Hand>().unwrap_err();
+        assert_eq!(err.to_string(), "Expected 5 cards, found 6");
+    }
+
+    #[test]
+    fn test_parse_invalid_card() {
+        assert!("AATAZ".parse::<Hand>().is_err());
+        assert!("AAAAA!".parse::<Hand>().is_err());
+        assert!("AAAA1".parse::<Hand>().is_err());
+    }
+
+    #[test]
+    fn test_cmp() {
+        let hand1 = "AAAAT".parse::<Hand>().unwrap();
+        let hand2 = "AAAA9".parse::<Hand>().unwrap();
+
+        assert!(hand1 > hand2);
+    }
+
+    #[test]
+    fn test_eq() {
+        let hand1 = "AAAAT".parse::<Hand>().unwrap();
+        let hand2 = "AAAA9".parse::<Hand>().unwrap();
+        let hand3 = "AATAA".parse::<Hand>().unwrap();
+
+        assert_eq!(hand1, hand1);
+        assert_eq!(hand1, "AAAAT".parse::<Hand>().unwrap());
+        assert_ne!(hand1, hand2);
+        // Same cards in a different order rank differently, so they aren't equal.
+        assert_ne!(hand1, hand3);
+        assert_eq!(hand1.cmp(&hand3), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn test_eq_matches_cmp() -> Result<()> {
+        let mut hands = vec![];
+        for s in [
+            "AAAAT", "AATAA", "TAAAA", "AAAA9", "KTJJT", "KJTJT", "JJJJJ", "23456",
+        ] {
+            hands.push(s.parse::<Hand>()?);
+            hands.push(Hand::parse_with_order(s, CardOrder::Joker)?);
+        }
+
+        for a in &hands {
+            for b in &hands {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{a} {b}");
+                assert_eq!(a.partial_cmp(b), Some(a.cmp(b)));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_dedup() -> Result<()> {
+        let mut hands = ["AATAA", "AAAAT", "AATAA", "32T3K", "AAAAT", "T3K32"]
+            .into_iter()
+            .map(str::parse)
+            .collect::<Result<Vec<Hand>>>()?;
+        hands.sort();
+        hands.dedup();
+
+        // Only exact repeats are dropped; rearrangements of the same cards stay.
+        assert_eq!(
+            hands.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["32T3K", "T3K32", "AATAA", "AAAAT"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ord() {
+        let hand1 = "AAAAT".parse::<Hand>().unwrap();
+        let hand2 = "AAAA9".parse::<Hand>().unwrap();
+
+        let mut hands = vec![hand1.clone(), hand2.clone()];
+        hands.sort();
+
+        assert_eq!(hands, vec![hand2, hand1]);
+    }
+
+    #[test]
+    fn test_display_roundtrip() -> Result<()> {
+        assert_eq!("KTJJT".parse::<Hand>()?.to_string(), "KTJJT");
+        for hand in ["32T3K", "T55J5", "KK677", "QQQJA", "AKQJT", "98765"] {
+            assert_eq!(hand.parse::<Hand>()?.to_string(), hand);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_frequency() -> Result<()> {
+        let hand = "T55J5".parse::<Hand>()?;
+        assert_eq!(
+            hand.frequency(),
+            vec![(Card::Five, 3), (Card::Ten, 1), (Card::Jack, 1)]
+        );
+
+        let hand = "KTJJT".parse::<Hand>()?;
+        assert_eq!(
+            hand.frequency(),
+            vec![(Card::Ten, 2), (Card::Jack, 2), (Card::King, 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_card_order() -> Result<()> {
+        let standard1 = "JKQA2".parse::<Hand>()?;
+        let standard2 = "2KQAT".parse::<Hand>()?;
+        assert_eq!(standard1.order(), CardOrder::Standard);
+        assert!(standard1 > standard2);
+
+        // Both are three of a kind once the joker is wild, so the joker loses on the first card.
+        let joker1 = Hand::parse_with_order("JKKA2", CardOrder::Joker)?;
+        let joker2 = Hand::parse_with_order("2KKAK", CardOrder::Joker)?;
+        assert_eq!(joker1.order(), CardOrder::Joker);
+        assert!(joker1 < joker2);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod hand_order_props {
+    use super::*;
+    use proptest::prelude::*;
+
+    // A handful of cards, jokers included, so equal hands and ties on type come up often.
+    fn hand() -> impl Strategy<Value = Hand> {
+        (
+            prop::collection::vec(prop::sample::select(vec!['2', 'T', 'J', 'Q', 'A']), 5),
+            any::<bool>(),
+        )
+            .prop_map(|(cards, joker)| {
+                let order = if joker {
+                    CardOrder::Joker
+                } else {
+                    CardOrder::Standard
+                };
+                Hand::parse_with_order(&cards.into_iter().collect::<String>(), order).unwrap()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn is_transitive(a in hand(), b in hand(), c in hand()) {
+            if a < b && b < c {
+                prop_assert!(a < c);
+            }
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+
+        #[test]
+        fn is_trichotomous(a in hand(), b in hand()) {
+            let holds = [a < b, a == b, a > b];
+            prop_assert_eq!(holds.iter().filter(|&&h| h).count(), 1);
+            prop_assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        }
+
+        #[test]
+        fn sort_is_idempotent(mut hands in prop::collection::vec(hand(), 0..20)) {
+            hands.sort();
+            prop_assert!(hands.windows(2).all(|w| w[0] <= w[1]));
+
+            let sorted = hands.clone();
+            hands.sort();
+            prop_assert_eq!(hands, sorted);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Card {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl TryFrom<char> for Card {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self> {
+        match c {
+            'A' => Ok(Self::Ace),
+            'K' => Ok(Self::King),
+            'Q' => Ok(Self::Queen),
+            'J' => Ok(Self::Jack),
+            'T' => Ok(Self::Ten),
+            '9' => Ok(Self::Nine),
+            '8' => Ok(Self::Eight),
+            '7' => Ok(Self::Seven),
+            '6' => Ok(Self::Six),
+            '5' => Ok(Self::Five),
+            '4' => Ok(Self::Four),
+            '3' => Ok(Self::Three),
+            '2' => Ok(Self::Two),
+            _ => Err(anyhow!("Invalid card")),
+        }
+    }
+}
+
+impl From<Card> for char {
+    fn from(card: Card) -> Self {
+        match card {
+            Card::Ace => 'A',
+            Card::King => 'K',
+            Card::Queen => 'Q',
+            Card::Jack => 'J',
+            Card::Ten => 'T',
+            Card::Nine => '9',
+            Card::Eight => '8',
+            Card::Seven => '7',
+            Card::Six => '6',
+            Card::Five => '5',
+            Card::Four => '4',
+            Card::Three => '3',
+            Card::Two => '2',
+        }
+    }
+}
+
+impl Card {
+    /// The card's strength when breaking ties under `order`.
+    pub fn rank(self, order: CardOrder) -> u8 {
+        match (self, order) {
+            (Card::Jack, CardOrder::Joker) => 0,
+            _ => self as u8 + 1,
+        }
+    }
+
+    /// The card's face value, from 2 for a Two up to 14 for an Ace.
+    pub fn value(self) -> u8 {
+        self as u8 + 2
+    }
+
+    /// Every card, weakest first.
+    pub fn all() -> impl Iterator<Item = Card> {
+        [
+            Card::Two,
+            Card::Three,
+            Card::Four,
+            Card::Five,
+            Card::Six,
+            Card::Seven,
+            Card::Eight,
+            Card::Nine,
+            Card::Ten,
+            Card::Jack,
+            Card::Queen,
+            Card::King,
+            Card::Ace,
+        ]
+        .into_iter()
+    }
+}
+
+/// Which rules a hand is played under, as chosen with `Hand::parse_with_order`,
+/// `Play::parse_with_order`, or `Plays::parse_with_order`. Parsing with `FromStr` always uses
+/// `Standard`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CardOrder {
+    /// Part 1: `J` is a Jack, between Ten and Queen.
+    #[default]
+    Standard,
+    /// Part 2: `J` is a wild Joker. It counts as whichever card makes the strongest hand type, but
+    /// ranks below Two when breaking ties.
+    Joker,
+}
+
+#[cfg(test)]
+mod card_tests {
+    use super::*;
+
+    #[test]
+    fn test_ord() {
+        assert!(Card::Ace > Card::King);
+        assert!(Card::King > Card::Queen);
+        assert!(Card::Queen > Card::Jack);
+        assert!(Card::Jack > Card::Ten);
+        assert!(Card::Ten > Card::Nine);
+        assert!(Card::Nine > Card::Eight);
+        assert!(Card::Eight > Card::Seven);
+        assert!(Card::Seven > Card::Six);
+        assert!(Card::Six > Card::Five);
+        assert!(Card::Five > Card::Four);
+        assert!(Card::Four > Card::Three);
+        assert!(Card::Three > Card::Two);
+    }
+
+    #[test]
+    fn test_char_roundtrip() -> Result<()> {
+        for c in "23456789TJQKA".chars() {
+            assert_eq!(char::from(Card::try_from(c)?), c);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_value() {
+        assert_eq!(Card::Two.value(), 2);
+        assert_eq!(Card::Ten.value(), 10);
+        assert_eq!(Card::Jack.value(), 11);
+        assert_eq!(Card::Ace.value(), 14);
+    }
+
+    #[test]
+    fn test_all() {
+        let cards = Card::all().collect::<Vec<_>>();
+        assert_eq!(cards.len(), 13);
+        assert!(cards.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(
+            cards.iter().map(|&c| char::from(c)).collect::<String>(),
+            "23456789TJQKA"
+        );
+        assert_eq!(
+            cards.iter().map(|c| c.value()).collect::<Vec<_>>(),
+            (2..=14).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        assert!(Card::Jack.rank(CardOrder::Standard) > Card::Ten.rank(CardOrder::Standard));
+        assert!(Card::Jack.rank(CardOrder::Joker) < Card::Two.rank(CardOrder::Joker));
+        assert_eq!(
+            Card::Ace.rank(CardOrder::Standard),
+            Card::Ace.rank(CardOrder::Joker)
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl From<&Hand> for HandType {
+    fn from(value: &Hand) -> Self {
+        value.hand_type
+    }
+}
+
+impl HandType {
+    /// Classifies a hand from its card frequencies, sorted in descending order, e.g. `[3, 2]` for a
+    /// full house.
+    fn classify(counts: &[usize]) -> Self {
+        match counts {
+            [5, ..] => Self::FiveOfAKind,
+            [4, ..] => Self::FourOfAKind,
+            [3, 2, ..] => Self::FullHouse,
+            [3, ..] => Self::ThreeOfAKind,
+            [2, 2, ..] => Self::TwoPair,
+            [2, ..] => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+
+    fn from_cards(cards: &[Card], order: CardOrder) -> Self {
+        let (jokers, others) = match order {
+            CardOrder::Standard => (0, cards.to_vec()),
+            CardOrder::Joker => {
+                let (jokers, others): (Vec<Card>, Vec<Card>) =
+                    cards.iter().partition(|&&card| card == Card::Jack);
+                (jokers.len(), others)
+            }
+        };
+
+        let mut counts = frequency(&others)
+            .into_iter()
+            .map(|(_, count)| count)
+            .collect::<Vec<_>>();
+
+        // Jokers join the most common card. A hand of only jokers is five of a kind.
+        match counts.first_mut() {
+            Some(most) => *most += jokers,
+            None => counts.push(jokers),
+        }
+
+        Self::classify(&counts)
+    }
+}
+
+// How many of each card there are, most common first. Ties are in card order.
+fn frequency(cards: &[Card]) -> Vec<(Card, usize)> {
+    let mut counts = counts(cards.iter().copied())
+        .into_iter()
+        .collect::<Vec<_>>();
+    counts.sort_unstable_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    counts
+}
+
+impl From<Hand> for HandType {
+    fn from(value: Hand) -> Self {
+        Self::from(&value)
+    }
+}
+
+#[cfg(test)]
+mod hand_type_tests {
+    use super::{CardOrder, Hand, HandType};
+    use anyhow::Result;
+
+    #[test]
+    fn test_parse_into() -> Result<()> {
+        let ht: HandType = "AAAAA".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::FiveOfAKind);
+
+        let ht: HandType = "AAAAQ".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::FourOfAKind);
+
+        let ht: HandType = "AAAQQ".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::FullHouse);
+
+        let ht: HandType = "AAAKQ".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::ThreeOfAKind);
+
+        let ht: HandType = "AAKKQ".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::TwoPair);
+
+        let ht: HandType = "AAKQJ".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::OnePair);
+
+        let ht: HandType = "AKQJT".parse::<Hand>()?.into();
+        assert_eq!(ht, HandType::HighCard);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jokers_promote() -> Result<()> {
+        let joker = |s| Hand::parse_with_order(s, CardOrder::Joker).map(HandType::from);
+
+        assert_eq!(joker("JJJJJ")?, HandType::FiveOfAKind);
+        assert_eq!(joker("QJJQ2")?, HandType::FourOfAKind);
+        assert_eq!(joker("KKJQQ")?, HandType::FullHouse);
+        assert_eq!(joker("T55J5")?, HandType::FourOfAKind);
+        assert_eq!(joker("AAKQJ")?, HandType::ThreeOfAKind);
+        assert_eq!(joker("AKQJT")?, HandType::OnePair);
+        assert_eq!(joker("AKQT9")?, HandType::HighCard);
+
+        // The same hands with Jacks.
+        assert_eq!(
+            HandType::from("JJJJJ".parse::<Hand>()?),
+            HandType::FiveOfAKind
+        );
+        assert_eq!(HandType::from("QJJQ2".parse::<Hand>()?), HandType::TwoPair);
+        assert_eq!(
+            HandType::from("T55J5".parse::<Hand>()?),
+            HandType::ThreeOfAKind
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_joker_breaks_ties_lowest() -> Result<()> {
+        // Both are four of a kind, but the joker loses the first-card comparison.
+        let hand1 = Hand::parse_with_order("JKKK2", CardOrder::Joker)?;
+        let hand2 = Hand::parse_with_order("QQQQ2", CardOrder::Joker)?;
+        assert!(hand1 < hand2);
+
+        // As a Jack it's only three of a kind.
+        assert!("JKKK2".parse::<Hand>()? < "QQQQ2".parse::<Hand>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(HandType::classify(&[5]), HandType::FiveOfAKind);
+        assert_eq!(HandType::classify(&[4, 1]), HandType::FourOfAKind);
+        assert_eq!(HandType::classify(&[3, 2]), HandType::FullHouse);
+        assert_eq!(HandType::classify(&[3, 1, 1]), HandType::ThreeOfAKind);
+        assert_eq!(HandType::classify(&[2, 2, 1]), HandType::TwoPair);
+        assert_eq!(HandType::classify(&[2, 1, 1, 1]), HandType::OnePair);
+        assert_eq!(HandType::classify(&[1, 1, 1, 1, 1]), HandType::HighCard);
+    }
+
+    #[test]
+    fn test_ord() -> Result<()> {
+        assert!(HandType::FiveOfAKind > HandType::FourOfAKind);
+        assert!(HandType::FourOfAKind > HandType::FullHouse);
+        assert!(HandType::FullHouse > HandType::ThreeOfAKind);
+        assert!(HandType::ThreeOfAKind > HandType::TwoPair);
+        assert!(HandType::TwoPair > HandType::OnePair);
+        assert!(HandType::OnePair > HandType::HighCard);
+
+        Ok(())
+    }
+}
+
+/// Every play in a puzzle input, one per line.
+#[derive(Clone, Debug)]
+pub struct Plays(pub Vec<Play>);
+
+impl FromStr for Plays {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with_order(s, CardOrder::Standard)
+    }
+}
+
+impl Plays {
+    /// Parses every play under `order`. `CardOrder::Joker` gives the part 2 rules.
+    pub fn parse_with_order(s: &str, order: CardOrder) -> Result<Self> {
+        let plays = normalize_lines(s)
+            .map(|line| Play::parse_with_order(line, order))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Plays(plays))
+    }
+
+    /// Each play's bid times its rank, where the weakest hand ranks 1.
+    pub fn total_winnings(&self) -> u64 {
+        score(&self.0).iter().sum()
+    }
+}
+
+/// Parses `input` and returns the total winnings of its plays.
+pub fn total_winnings(input: &str) -> Result<u64> {
+    input.parse::<Plays>().map(|plays| plays.total_winnings())
+}
+
+// sorts plays by rank
+fn score(plays: &[Play]) -> Vec<u64> {
+    let mut plays = plays.to_vec();
+    plays.sort_by(|a, b| a.hand.cmp(&b.hand));
+    plays
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i as u64 + 1) * p.bid)
+        .collect()
+}
+
+#[cfg(test)]
+mod rank_tests {
+    use super::{score, total_winnings, CardOrder, Plays};
+    use anyhow::Result;
+
+    static INPUT: &str = "\
+    32T3K 765
+    T55J5 684
+    KK677 28
+    KTJJT 220
+    QQQJA 483";
+
+    #[test]
+    #[allow(clippy::identity_op)]
+    fn test_score() -> Result<()> {
+        let plays: Plays = INPUT.parse()?;
+        assert_eq!(
+            score(&plays.0),
+            vec![765 * 1, 220 * 2, 28 * 3, 684 * 4, 483 * 5]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_total_winnings() -> Result<()> {
+        assert_eq!(INPUT.parse::<Plays>()?.total_winnings(), 6440);
+        assert_eq!(total_winnings(INPUT)?, 6440);
+        assert!(total_winnings("32T3K x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_total_winnings_with_jokers() -> Result<()> {
+        let plays = Plays::parse_with_order(INPUT, CardOrder::Joker)?;
+        assert_eq!(plays.total_winnings(), 5905);
+        assert_eq!(
+            Plays::parse_with_order(INPUT, CardOrder::Standard)?.total_winnings(),
+            6440
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_bid() {
+        let err = "32T3K 765\nT55J5 lots".parse::<Plays>().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid bid");
+
+        assert!("32T3K".parse::<Plays>().is_err());
+        assert!("32T3 765".parse::<Plays>().is_err());
+    }
+
+    #[test]
+    fn test_large_bids() -> Result<()> {
+        // 5000 hands at a trillion each is far past u32, but the total still fits in a u64.
+        let bid = 1_000_000_000_000u64;
+        let input = "23456789TJQKA"
+            .repeat(400)
+            .chars()
+            .collect::<Vec<_>>()
+            .windows(5)
+            .take(5000)
+            .map(|cards| format!("{} {bid}", cards.iter().collect::<String>()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let plays: Plays = input.parse()?;
+        assert_eq!(plays.0.len(), 5000);
+        assert_eq!(plays.total_winnings(), bid * (5000 * 5001 / 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_crlf() -> Result<()> {
+        let crlf = INPUT.replace('\n', "\r\n");
+        assert_eq!(
+            crlf.parse::<Plays>()?.total_winnings(),
+            INPUT.parse::<Plays>()?.total_winnings()
+        );
+        Ok(())
+    }
+}